@@ -0,0 +1,50 @@
+use std::process;
+
+use clap::Args;
+
+use crate::instance::{InstanceFormat, read_instance};
+
+#[derive(Debug, Args)]
+pub struct Check {
+    /// The path to the instance file (`-` for the standard input)
+    #[clap(short, long)]
+    pub instance: String,
+    /// The format of the instance file
+    #[clap(short, long, value_enum, default_value="json")]
+    pub format: InstanceFormat,
+    /// The number of runways used when the instance format does not specify it
+    #[clap(short='r', long, default_value="1")]
+    pub nb_runways: usize,
+}
+
+impl Check {
+    pub fn check(&self) {
+        let instance = read_instance(&self.instance, self.format, self.nb_runways).unwrap_or_else(|e| {
+            eprintln!("{}: {e}", self.instance);
+            process::exit(1);
+        });
+
+        let errors = instance.validate();
+        for error in errors.iter() {
+            println!("{}: {error}", self.instance);
+        }
+
+        if errors.is_empty() && !instance.separation_is_symmetric() {
+            eprintln!("{}: warning: the separation matrix is not symmetric", self.instance);
+        }
+        if errors.is_empty() && instance.has_negative_separation() {
+            eprintln!("{}: warning: the negative separation times are treated as zero", self.instance);
+        }
+
+        if errors.is_empty() {
+            println!("{}: valid", self.instance);
+            match instance.expected_feasible {
+                Some(true) => println!("{}: expected feasible", self.instance),
+                Some(false) => println!("{}: expected infeasible", self.instance),
+                None => (),
+            }
+        } else {
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::{io::{Read, Write}, process};
+
+use clap::Args;
+
+use crate::instance::{orlib, open_input, create_output, or_exit};
+
+#[derive(Debug, Args)]
+pub struct Convert {
+    /// The path to the OR-Library instance file (`-` for the standard input)
+    #[clap(short, long)]
+    pub instance: String,
+    /// The number of runways of the converted instance
+    #[clap(short='r', long, default_value="1")]
+    pub nb_runways: usize,
+    /// The factor by which the times are multiplied before being rounded, to keep their decimals
+    #[clap(long, default_value="1")]
+    pub time_scale: f64,
+    /// Name of the file where to write the converted alp instance
+    #[clap(short, long)]
+    pub output: Option<String>,
+}
+
+impl Convert {
+    pub fn convert(&self) {
+        let mut text = String::new();
+        if let Err(e) = open_input(&self.instance).and_then(|mut input| input.read_to_string(&mut text)) {
+            eprintln!("{}: {e}", self.instance);
+            process::exit(1);
+        }
+        let instance = orlib::parse(&text, self.nb_runways, self.time_scale).unwrap_or_else(|e| {
+            eprintln!("{}: {e}", self.instance);
+            process::exit(1);
+        });
+
+        let instance = serde_json::to_string_pretty(&instance).unwrap();
+
+        if let Some(output) = self.output.as_ref() {
+            or_exit(output, or_exit(output, create_output(output)).write_all(instance.as_bytes()));
+        } else {
+            println!("{instance}");
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use std::process;
+
+use clap::Args;
+
+use crate::instance::{InstanceFormat, read_instance};
+
+#[derive(Debug, Args)]
+pub struct Describe {
+    /// The path to the instance file (`-` for the standard input)
+    #[clap(short, long)]
+    pub instance: String,
+    /// The format of the instance file
+    #[clap(short, long, value_enum, default_value="json")]
+    pub format: InstanceFormat,
+    /// The number of runways used when the instance format does not specify it
+    #[clap(short='r', long, default_value="1")]
+    pub nb_runways: usize,
+}
+
+impl Describe {
+    pub fn describe(&self) {
+        let instance = read_instance(&self.instance, self.format, self.nb_runways).unwrap_or_else(|e| {
+            eprintln!("{}: {e}", self.instance);
+            process::exit(1);
+        });
+
+        let errors = instance.validate();
+        if !errors.is_empty() {
+            for error in errors.iter() {
+                eprintln!("{}: {error}", self.instance);
+            }
+            process::exit(1);
+        }
+
+        println!("aircrafts {}", instance.nb_aircrafts);
+        println!("runways {}", instance.nb_runways);
+        println!("classes {}", instance.nb_classes);
+
+        match instance.time_horizon() {
+            Some((start, end)) => println!("horizon {start} .. {end}"),
+            None => println!("horizon n/a"),
+        }
+        println!("planning horizon {}", instance.horizon());
+        match instance.separation_stats() {
+            Some((mean, max)) => println!("separation mean {mean:.1} max {max}"),
+            None => println!("separation n/a"),
+        }
+
+        let duplicates = instance.duplicate_groups();
+        println!("duplicate aircrafts {} in {} groups", duplicates.iter().map(|group| group.len()).sum::<usize>(), duplicates.len());
+
+        println!("class  aircrafts  avg slack");
+        for (class, (count, slack)) in instance.class_histogram().into_iter().zip(instance.average_slack_per_class()).enumerate() {
+            let class = instance.class_name(class);
+            match slack {
+                Some(slack) => println!("{class:>5}  {count:>9}  {slack:>9.1}"),
+                None => println!("{class:>5}  {count:>9}  {:>9}", "n/a"),
+            }
+        }
+    }
+}
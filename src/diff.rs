@@ -0,0 +1,95 @@
+use std::process;
+
+use clap::Args;
+
+use crate::instance::{AlpInstance, InstanceFormat, read_instance};
+
+#[derive(Debug, Args)]
+pub struct Diff {
+    /// The path to the first instance file
+    pub first: String,
+    /// The path to the second instance file
+    pub second: String,
+}
+
+impl Diff {
+    /// Prints the differences between the two instances and exits with code 1 if there is any
+    pub fn diff(&self) {
+        let first = self.read(&self.first);
+        let second = self.read(&self.second);
+
+        let differences = differences(&first, &second);
+        for difference in differences.iter() {
+            println!("{difference}");
+        }
+
+        if !differences.is_empty() {
+            process::exit(1);
+        }
+    }
+
+    fn read(&self, path: &str) -> AlpInstance {
+        let instance = read_instance(path, InstanceFormat::Json, 1).unwrap_or_else(|e| {
+            eprintln!("{path}: {e}");
+            process::exit(1);
+        });
+
+        let errors = instance.validate();
+        if !errors.is_empty() {
+            for error in errors.iter() {
+                eprintln!("{path}: {error}");
+            }
+            process::exit(1);
+        }
+
+        instance
+    }
+}
+
+/// Lists the differences between the dimensions, the aircrafts and the separation times of two
+/// instances, the aircrafts and classes being compared up to the size of the smallest instance.
+/// The instances which differ in any other field get a single catch-all difference.
+pub fn differences(first: &AlpInstance, second: &AlpInstance) -> Vec<String> {
+    let mut differences = vec![];
+
+    for (what, a, b) in [
+        ("aircrafts", first.nb_aircrafts, second.nb_aircrafts),
+        ("runways", first.nb_runways, second.nb_runways),
+        ("classes", first.nb_classes, second.nb_classes),
+    ] {
+        if a != b {
+            differences.push(format!("{what}: {a} -> {b}"));
+        }
+    }
+
+    for aircraft in 0..first.nb_aircrafts.min(second.nb_aircrafts) {
+        if first.classes[aircraft] != second.classes[aircraft] {
+            differences.push(format!("aircraft {aircraft} class: {} -> {}", first.classes[aircraft], second.classes[aircraft]));
+        }
+        for (what, a, b) in [
+            ("target", first.target[aircraft], second.target[aircraft]),
+            ("latest", first.latest[aircraft], second.latest[aircraft]),
+        ] {
+            if a != b {
+                differences.push(format!("aircraft {aircraft} {what}: {a} -> {b} ({:+})", b - a));
+            }
+        }
+    }
+
+    let nb_classes = first.nb_classes.min(second.nb_classes);
+    for (runway, (a, b)) in first.separation.matrices().into_iter().zip(second.separation.matrices()).enumerate() {
+        for i in 0..nb_classes {
+            for j in 0..nb_classes {
+                if a[i][j] != b[i][j] {
+                    differences.push(format!("runway {runway} separation[{i}][{j}]: {} -> {}", a[i][j], b[i][j]));
+                }
+            }
+        }
+    }
+
+    if differences.is_empty() && first != second {
+        differences.push("other fields differ".to_string());
+    }
+
+    differences
+}
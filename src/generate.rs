@@ -1,80 +1,326 @@
-use std::{time::{SystemTime, UNIX_EPOCH}, fs::File, io::Write};
+use std::{time::{SystemTime, UNIX_EPOCH}, fs::{self, File}, io::Write, path::Path, process};
 
-use clap::Args;
-use rand::{Rng, SeedableRng};
+use clap::{Args, ValueEnum};
+use rand::{Rng, SeedableRng, seq::SliceRandom};
 use rand_chacha::ChaChaRng;
-use rand_distr::{Uniform, Normal, Distribution};
+use rand_distr::{Uniform, Normal, Distribution, WeightedIndex};
 
-use crate::instance::AlpInstance;
+use crate::instance::{create_output, or_exit, AlpInstance, Separation};
+
+/// The distribution of the target times
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ArrivalModel {
+    /// Exponentially distributed interarrival times
+    Exponential,
+    /// Targets exactly spaced by the average interarrival time
+    UniformGrid,
+    /// Targets drawn from a mixture of two normal distributions (morning and evening rushes)
+    BimodalPeak,
+}
+
+/// How the real-valued draws are rounded to integer times
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RoundMode {
+    /// Round to the nearest integer, away from zero on ties
+    Nearest,
+    /// Round towards negative infinity
+    Floor,
+    /// Round towards positive infinity
+    Ceil,
+}
+
+impl RoundMode {
+    pub fn apply(self, value: f64) -> isize {
+        match self {
+            RoundMode::Nearest => value.round() as isize,
+            RoundMode::Floor => value.floor() as isize,
+            RoundMode::Ceil => value.ceil() as isize,
+        }
+    }
+}
 
 #[derive(Debug, Args)]
 pub struct AlpGenerator {
     /// An optional seed to kickstart the instance generation
     #[clap(short='s', long)]
-    seed: Option<u128>,
+    pub seed: Option<u128>,
     /// The number of aircrafts
     #[clap(short='n', long, default_value="50")]
-    nb_aircrafts: usize,
+    pub nb_aircrafts: usize,
     /// The number of runways
     #[clap(short='r', long, default_value="5")]
-    nb_runways: usize,
+    pub nb_runways: usize,
     /// The number of aircraft classes
     #[clap(short='k', long, default_value="4")]
-    nb_classes: usize,
+    pub nb_classes: usize,
+    /// The comma-separated relative frequencies of the classes, which are normalized (all
+    /// classes are equally frequent when absent)
+    #[clap(long, value_delimiter=',')]
+    pub class_weights: Vec<f64>,
     /// The number of clusters of similar classes
     #[clap(short='c', long, default_value="2")]
-    nb_clusters: usize,
+    pub nb_clusters: usize,
     /// The minimum separation position used to generate the pairwise minimum separation time
     #[clap(long, default_value="100")]
-    min_separation_position: isize,
+    pub min_separation_position: isize,
     /// The maximum separation position used to generate the pairwise minimum separation time
     #[clap(long, default_value="300")]
-    max_separation_position: isize,
+    pub max_separation_position: isize,
     /// The std deviation of the separation positions among a cluster
     #[clap(long, default_value="20")]
-    separation_position_std_dev: isize,
+    pub separation_position_std_dev: isize,
     /// The average time between two aircraft arrivals
     #[clap(long, default_value="40")]
-    avg_interarrival_time: isize,
+    pub avg_interarrival_time: isize,
+    /// The smallest slack between the target and latest times
+    #[clap(long, default_value="0")]
+    pub min_slack: isize,
+    /// The slack between the target and latest times is drawn below this value (5 times the
+    /// average interarrival time when absent)
+    #[clap(long)]
+    pub max_slack: Option<isize>,
+    /// The fraction of aircrafts whose latest time is as close as possible to their target time
+    #[clap(long, default_value="0")]
+    pub tight_fraction: f64,
+    /// The distribution of the target times
+    #[clap(long, value_enum, default_value="exponential")]
+    pub arrival_model: ArrivalModel,
+    /// How the drawn times are rounded to integers
+    #[clap(long, value_enum, default_value="nearest")]
+    pub round_mode: RoundMode,
+    /// The probability that an aircraft is not allowed to land on a given runway
+    #[clap(long, default_value="0")]
+    pub runway_restriction_prob: f64,
+    /// Derive the separations from a random weight rank of each class instead of the clusters:
+    /// the heavier the leading class and the lighter the following one, the larger the separation
+    #[clap(long)]
+    pub wake_model: bool,
+    /// Make the separation matrix symmetric by taking the max of sep[i][j] and sep[j][i]
+    #[clap(long)]
+    pub symmetrize: bool,
+    /// Close all runways during a nightly curfew at the end of each day
+    #[clap(long)]
+    pub curfews: bool,
+    /// The length of a day, used to place the curfews
+    #[clap(long, default_value="1440")]
+    pub day_length: isize,
+    /// The duration of the nightly curfews
+    #[clap(long, default_value="360")]
+    pub curfew_duration: isize,
+    /// Crush the time windows of some aircrafts of a class so that the instance has no feasible
+    /// schedule, which is then recorded in the instance
+    #[clap(long)]
+    pub force_infeasible: bool,
+    /// Give the synthetic names `C0`, `C1`, ... to the classes
+    #[clap(long)]
+    pub class_names: bool,
     /// Name of the file where to generate the alp instance
     #[clap(short, long)]
-    output: Option<String>,
+    pub output: Option<String>,
+    /// The number of instances to generate, each with the seed following the one of the previous instance
+    #[clap(long, default_value="1")]
+    pub count: usize,
+    /// Name of the directory where to generate the instances when more than one is generated
+    #[clap(long, default_value=".")]
+    pub output_dir: String,
 }
 
 impl AlpGenerator {
 
     pub fn generate(&mut self) {
-        let mut rng = self.rng();
+        self.check_parameters();
+
+        let seed = self.first_seed();
+
+        if self.count == 1 {
+            eprintln!("seed: {seed}");
+
+            let instance = self.build_seeded(seed);
+            eprintln!("class histogram: {:?}", instance.class_histogram());
+            let instance = serde_json::to_string_pretty(&instance).unwrap();
+
+            if let Some(output) = self.output.as_ref() {
+                or_exit(output, or_exit(output, create_output(output)).write_all(instance.as_bytes()));
+            } else {
+                println!("{instance}");
+            }
+        } else {
+            or_exit(&self.output_dir, fs::create_dir_all(&self.output_dir));
+
+            let mut histogram = vec![0; self.nb_classes];
+            for i in 0..self.count {
+                let instance = self.build_seeded(seed.wrapping_add(i as u128));
+                histogram.iter_mut().zip(instance.class_histogram()).for_each(|(total, count)| *total += count);
+                let instance = serde_json::to_string_pretty(&instance).unwrap();
+                let path = Path::new(&self.output_dir).join(format!("instance_{i:04}.json"));
+                or_exit(&path, or_exit(&path, File::create(&path)).write_all(instance.as_bytes()));
+            }
+
+            eprintln!("wrote {} instances in {} with seeds starting from {seed}", self.count, self.output_dir);
+            eprintln!("class histogram: {histogram:?}");
+        }
+    }
+
+    /// Exits when the parameters are inconsistent
+    pub fn check_parameters(&self) {
+        if self.max_slack() <= self.min_slack {
+            eprintln!("the max slack {} must be larger than the min slack {}", self.max_slack(), self.min_slack);
+            process::exit(1);
+        }
+        if !self.class_weights.is_empty() {
+            if self.class_weights.len() != self.nb_classes {
+                eprintln!("{} class weights are given for {} classes", self.class_weights.len(), self.nb_classes);
+                process::exit(1);
+            }
+            if self.class_weights.iter().any(|w| !w.is_finite() || *w < 0.0) || self.class_weights.iter().sum::<f64>() <= 0.0 {
+                eprintln!("the class weights must be nonnegative and not all zero");
+                process::exit(1);
+            }
+        }
+    }
+
+    /// The seed of the first instance, drawn from the clock when none is given
+    pub fn first_seed(&self) -> u128 {
+        self.seed.unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis())
+    }
+
+    /// Generates the instance of the given seed, exits when its infeasibility cannot be forced
+    pub fn build_seeded(&self, seed: u128) -> AlpInstance {
+        let mut instance = self.build_instance(&mut self.rng(seed));
+        instance.seed = Some(seed);
+
+        if self.force_infeasible && instance.expected_feasible != Some(false) {
+            eprintln!("seed {seed}: cannot force the infeasibility, no class has more aircrafts than runways and a positive separation to every class");
+            process::exit(1);
+        }
 
+        instance
+    }
+
+    /// Generates an instance with the given random number generator, without any I/O.
+    ///
+    /// The generated instances are always feasible: the latest times are widened so that the
+    /// greedy schedule of `AlpInstance::greedy_arrivals` meets all of them, while remaining
+    /// nondecreasing within each class.
+    pub fn build_instance(&self, rng: &mut impl Rng) -> AlpInstance {
         let mut nb_classes_per_cluster = vec![self.nb_classes / self.nb_clusters; self.nb_clusters];
         for i in 0..(self.nb_classes % self.nb_clusters) {
             nb_classes_per_cluster[i] += 1;
         }
         
-        let classes = self.generate_classes(&mut rng);
-        let separation = self.generate_separation_costs(&mut rng, &nb_classes_per_cluster);
-        let target = self.generate_target(&mut rng);
-        let latest = self.generate_latest(&mut rng, &target, &classes);
+        let classes = self.generate_classes(rng);
+        let separation = if self.wake_model {
+            self.generate_wake_separation_costs(rng)
+        } else {
+            self.generate_separation_costs(rng, &nb_classes_per_cluster)
+        };
+        let target = self.generate_target(rng);
+        let latest = self.generate_latest(rng, &target, &classes);
+        let earliest = self.generate_earliest(rng, &target);
+        let eligible_runways = self.generate_eligible_runways(rng);
+        let appearance = self.generate_appearance(rng, &target);
 
-        let instance = AlpInstance {
+        let mut instance = AlpInstance {
             nb_aircrafts: self.nb_aircrafts,
             nb_runways: self.nb_runways,
             nb_classes: self.nb_classes,
-            separation,
+            separation: Separation::Global(separation),
             classes,
             target,
             latest,
+            earliest,
+            appearance,
+            earliness_cost: vec![],
+            lateness_cost: vec![],
+            eligible_runways,
+            fixed_runway: vec![],
+            runway_unavailable: vec![],
+            precedence: vec![],
+            time_scale: None,
+            seed: None,
+            class_names: if self.class_names { (0..self.nb_classes).map(|c| format!("C{c}")).collect() } else { vec![] },
+            expected_feasible: None,
+            daily_curfew: None,
+            day_length: None,
+            horizon: None,
+            separation_schedule: vec![],
         };
 
-        let instance = serde_json::to_string_pretty(&instance).unwrap();
+        instance.runway_unavailable = self.generate_curfews(&mut instance);
 
-        if let Some(output) = self.output.as_ref() {
-            File::create(output).unwrap().write_all(instance.as_bytes()).unwrap();
-        } else {
-            println!("{instance}");
+        if self.symmetrize {
+            instance.symmetrize();
+        }
+
+        Self::widen_latest(&mut instance);
+
+        if self.force_infeasible && Self::crush_latest(&mut instance) {
+            instance.expected_feasible = Some(false);
+        }
+
+        instance
+    }
+
+    /// Makes the instance infeasible by giving the target and latest times of the first aircraft
+    /// of a class to the next aircrafts of that class, one more than there are runways. Two of
+    /// them must then land at the same time on the same runway, which is impossible when the
+    /// separation from their class to any class is positive on every runway. Returns false and
+    /// leaves the instance untouched when no class has enough aircrafts and such separations.
+    fn crush_latest(instance: &mut AlpInstance) -> bool {
+        let histogram = instance.class_histogram();
+        let class = (0..instance.nb_classes)
+            .filter(|c| histogram[*c] > instance.nb_runways)
+            .find(|c| instance.separation.matrices().iter().all(|matrix| matrix[*c].iter().all(|s| *s > 0)));
+        let class = match class {
+            Some(class) => class,
+            None => return false,
+        };
+
+        let crushed = (0..instance.nb_aircrafts).filter(|a| instance.classes[*a] == class).take(instance.nb_runways + 1).collect::<Vec<usize>>();
+        let time = instance.target[crushed[0]];
+        for aircraft in crushed {
+            instance.target[aircraft] = time;
+            instance.latest[aircraft] = time;
+            if !instance.earliest.is_empty() {
+                instance.earliest[aircraft] = instance.earliest[aircraft].min(time);
+            }
+            if !instance.appearance.is_empty() {
+                instance.appearance[aircraft] = instance.appearance[aircraft].min(time);
+            }
+        }
+
+        true
+    }
+
+    /// Postpones the latest times that the greedy schedule does not meet. Every aircraft keeps
+    /// an eligible runway, hence all the greedy arrival times are finite.
+    fn widen_latest(instance: &mut AlpInstance) {
+        let arrivals = instance.greedy_arrivals();
+        let mut last = vec![isize::MIN; instance.nb_classes];
+
+        for (i, arrival) in arrivals.into_iter().enumerate() {
+            let class = instance.classes[i];
+            instance.latest[i] = instance.latest[i].max(arrival).max(last[class]);
+            last[class] = instance.latest[i];
         }
     }
 
+    /// The separation grows linearly with the difference between the weight ranks of the leading
+    /// and following classes, from the min separation position when the lightest class leads the
+    /// heaviest one to the max separation position in the opposite case
+    fn generate_wake_separation_costs(&self, rng: &mut impl Rng) -> Vec<Vec<isize>> {
+        let mut rank = (0..self.nb_classes).collect::<Vec<usize>>();
+        rank.shuffle(rng);
+
+        let spread = (self.nb_classes.max(2) - 1) as f64;
+        let range = (self.max_separation_position - self.min_separation_position) as f64;
+
+        (0..self.nb_classes).map(|i| (0..self.nb_classes).map(|j| {
+            let difference = (rank[i] as f64 - rank[j] as f64) / spread;
+            self.min_separation_position + self.round_mode.apply(range * (1.0 + difference) / 2.0)
+        }).collect()).collect()
+    }
+
     fn generate_separation_costs(&self, rng: &mut impl Rng, nb_classes_per_cluster: &Vec<usize>) -> Vec<Vec<isize>> {
         let mut members = vec![vec![]; self.nb_clusters];
         let mut t = 0_usize;
@@ -91,7 +337,7 @@ impl AlpGenerator {
         let rand_position = Normal::new(0.0, self.separation_position_std_dev as f64).expect("cannot create normal dist");
         for a in 0..self.nb_clusters {
             let centroid_a = rand_centroid.sample(rng);
-            let positions_a = (0..nb_classes_per_cluster[a]).map(|_| centroid_a + rand_position.sample(rng).round() as isize).collect::<Vec<isize>>();
+            let positions_a = (0..nb_classes_per_cluster[a]).map(|_| centroid_a + self.round_mode.apply(rand_position.sample(rng))).collect::<Vec<isize>>();
 
             for b in 0..self.nb_clusters {
                 if a == b {
@@ -99,7 +345,7 @@ impl AlpGenerator {
                         for (j, tj) in members[a].iter().copied().enumerate() {
                             if ti == tj {
                                 while separation_costs[ti][tj] < 0 {
-                                    separation_costs[ti][tj] = self.separation_position_std_dev + rand_position.sample(rng).round() as isize;
+                                    separation_costs[ti][tj] = self.separation_position_std_dev + self.round_mode.apply(rand_position.sample(rng));
                                 }
                             } else {
                                 separation_costs[ti][tj] = positions_a[i].abs_diff(positions_a[j]) as isize;
@@ -108,7 +354,7 @@ impl AlpGenerator {
                     }
                 } else {
                     let centroid_b = rand_centroid.sample(rng);
-                    let positions_b = (0..nb_classes_per_cluster[b]).map(|_| centroid_b + rand_position.sample(rng).round() as isize).collect::<Vec<isize>>();
+                    let positions_b = (0..nb_classes_per_cluster[b]).map(|_| centroid_b + self.round_mode.apply(rand_position.sample(rng))).collect::<Vec<isize>>();
 
                     for (i, ti) in members[a].iter().copied().enumerate() {
                         for (j, tj) in members[b].iter().copied().enumerate() {
@@ -122,37 +368,82 @@ impl AlpGenerator {
         separation_costs
     }
 
+    /// Draws the class of each aircraft, with the class weights when given. The weights are
+    /// assumed to be validated by `generate`.
     fn generate_classes(&self, rng: &mut impl Rng) -> Vec<usize> {
         let mut classes = vec![];
-        
-        let rand_class = Uniform::new(0, self.nb_classes);
 
-        for _ in 0..self.nb_aircrafts {
-            classes.push(rand_class.sample(rng));
+        if self.class_weights.is_empty() {
+            let rand_class = Uniform::new(0, self.nb_classes);
+            for _ in 0..self.nb_aircrafts {
+                classes.push(rand_class.sample(rng));
+            }
+        } else {
+            let rand_class = WeightedIndex::new(&self.class_weights).unwrap();
+            for _ in 0..self.nb_aircrafts {
+                classes.push(rand_class.sample(rng));
+            }
         }
 
         classes
     }
 
     fn generate_target(&self, rng: &mut impl Rng) -> Vec<isize> {
+        match self.arrival_model {
+            ArrivalModel::Exponential => self.generate_exponential_target(rng),
+            ArrivalModel::UniformGrid => (0..self.nb_aircrafts).map(|i| i as isize * self.avg_interarrival_time).collect(),
+            ArrivalModel::BimodalPeak => self.generate_bimodal_target(rng),
+        }
+    }
+
+    fn generate_exponential_target(&self, rng: &mut impl Rng) -> Vec<isize> {
         let mut target = vec![0];
 
         let rand = Uniform::<f64>::new(0.0, 1.0);
 
+        // each gap is at least 1 so that a small draw does not make two targets coincide
         for i in 1..self.nb_aircrafts {
-            target.push(target[i - 1] +  (- rand.sample(rng).ln() * self.avg_interarrival_time as f64 / self.nb_runways as f64).round() as isize);
+            let gap = self.round_mode.apply(- rand.sample(rng).ln() * self.avg_interarrival_time as f64 / self.nb_runways as f64);
+            target.push(target[i - 1] + gap.max(1));
         }
 
         target
     }
 
+    /// The two peaks are centered at a quarter and three quarters of the horizon over which the
+    /// exponential model would spread the arrivals
+    fn generate_bimodal_target(&self, rng: &mut impl Rng) -> Vec<isize> {
+        let horizon = (self.nb_aircrafts as isize * self.avg_interarrival_time) as f64 / self.nb_runways as f64;
+
+        let rand_peak = Uniform::<f64>::new(0.0, 1.0);
+        let morning = Normal::new(horizon / 4.0, horizon / 10.0).expect("cannot create normal dist");
+        let evening = Normal::new(3.0 * horizon / 4.0, horizon / 10.0).expect("cannot create normal dist");
+
+        let mut target = (0..self.nb_aircrafts).map(|_| {
+            let time = if rand_peak.sample(rng) < 0.5 { morning.sample(rng) } else { evening.sample(rng) };
+            self.round_mode.apply(time).max(0)
+        }).collect::<Vec<isize>>();
+        target.sort_unstable();
+
+        target
+    }
+
     fn generate_latest(&self, rng: &mut impl Rng, target: &Vec<isize>, classes: &Vec<usize>) -> Vec<isize> {
         let mut latest = vec![];
         let mut last = vec![0; self.nb_classes];
 
-        let rand = Uniform::new(0, 5 * self.avg_interarrival_time);
+        let rand = Uniform::new(self.min_slack, self.max_slack());
+        let rand_tight = Uniform::<f64>::new(0.0, 1.0);
 
         for i in 0..self.nb_aircrafts {
+            // the tight windows still respect the order of the latest times within each class
+            if self.tight_fraction > 0.0 && rand_tight.sample(rng) < self.tight_fraction {
+                let end = target[i].max(last[classes[i]]);
+                latest.push(end);
+                last[classes[i]] = end;
+                continue;
+            }
+
             loop {
                 let end = target[i] + rand.sample(rng);
                 if end >= last[classes[i]] {
@@ -166,12 +457,150 @@ impl AlpGenerator {
         latest
     }
 
-    fn rng(&self) -> impl Rng {
-        let init = self.seed.unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis());
+    /// Each aircraft appears up to 5 average interarrival times before its target time
+    fn generate_appearance(&self, rng: &mut impl Rng, target: &Vec<isize>) -> Vec<isize> {
+        let rand = Uniform::new(0, 5 * self.avg_interarrival_time);
+
+        target.iter().map(|t| t - rand.sample(rng)).collect()
+    }
+
+    fn max_slack(&self) -> isize {
+        self.max_slack.unwrap_or(5 * self.avg_interarrival_time)
+    }
+
+    fn generate_earliest(&self, rng: &mut impl Rng, target: &Vec<isize>) -> Vec<isize> {
+        let rand = Uniform::new(0, self.avg_interarrival_time);
+
+        target.iter().map(|t| t - rand.sample(rng)).collect()
+    }
+
+    /// Each aircraft keeps at least one eligible runway so that the instance remains solvable
+    fn generate_eligible_runways(&self, rng: &mut impl Rng) -> Vec<Vec<bool>> {
+        if self.runway_restriction_prob <= 0.0 {
+            return vec![];
+        }
+
+        let rand = Uniform::<f64>::new(0.0, 1.0);
+        let rand_runway = Uniform::new(0, self.nb_runways);
+
+        (0..self.nb_aircrafts).map(|_| {
+            let mut eligible = (0..self.nb_runways).map(|_| rand.sample(rng) >= self.runway_restriction_prob).collect::<Vec<bool>>();
+            if !eligible.iter().any(|e| *e) {
+                eligible[rand_runway.sample(rng)] = true;
+            }
+            eligible
+        }).collect()
+    }
+
+    /// Closes all runways at the end of each day up to the horizon of the instance, the latest
+    /// times falling in a curfew are postponed to its end so that the aircrafts can still land
+    fn generate_curfews(&self, instance: &mut AlpInstance) -> Vec<Vec<(isize, isize)>> {
+        if !self.curfews {
+            return vec![];
+        }
+
+        let curfews = (0..=instance.horizon() / self.day_length)
+            .map(|day| ((day + 1) * self.day_length - self.curfew_duration, (day + 1) * self.day_length))
+            .collect::<Vec<(isize, isize)>>();
+
+        for l in instance.latest.iter_mut() {
+            if let Some((_, end)) = curfews.iter().find(|(start, end)| *start <= *l && *l < *end) {
+                *l = *end;
+            }
+        }
+
+        vec![curfews; self.nb_runways]
+    }
+
+    /// Expands the given seed into the 32 bytes of the ChaCha seed: the lower and upper halves
+    /// of the seed each initialize a SplitMix64 generator producing two words of the final seed.
+    /// Since each SplitMix64 step is a bijection of its state, distinct seeds yield distinct
+    /// ChaCha seeds.
+    fn rng(&self, init: u128) -> impl Rng {
         let mut seed = [0_u8; 32];
-        seed.iter_mut().zip(init.to_be_bytes().into_iter()).for_each(|(s, i)| *s = i);
-        seed.iter_mut().rev().zip(init.to_le_bytes().into_iter()).for_each(|(s, i)| *s = i);
+        let halves = [init as u64, (init >> 64) as u64];
+        for (chunk, half) in seed.chunks_exact_mut(16).zip(halves) {
+            let mut state = half;
+            for word in chunk.chunks_exact_mut(8) {
+                word.copy_from_slice(&Self::splitmix64(&mut state).to_le_bytes());
+            }
+        }
         ChaChaRng::from_seed(seed)
     }
 
-}
\ No newline at end of file
+    fn splitmix64(state: &mut u64) -> u64 {
+        *state = state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A generator configured by the given command line arguments
+    #[cfg(test)]
+    pub(crate) fn from_args(args: &[&str]) -> Self {
+        #[derive(clap::Parser)]
+        struct Command {
+            #[clap(flatten)]
+            generator: AlpGenerator,
+        }
+
+        <Command as clap::Parser>::parse_from(std::iter::once("generate").chain(args.iter().copied())).generator
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_instances_are_feasible() {
+        let settings: [&[&str]; 6] = [
+            &["-n", "30", "-r", "1"],
+            &["-n", "30", "-r", "3", "--tight-fraction", "0.5"],
+            &["-n", "30", "-r", "2", "--runway-restriction-prob", "0.5"],
+            &["-n", "30", "-r", "2", "--curfews", "--day-length", "400", "--curfew-duration", "100"],
+            &["-n", "30", "-r", "2", "--arrival-model", "bimodal-peak", "--max-slack", "1"],
+            &["-n", "30", "-r", "2", "--wake-model", "--symmetrize", "--avg-interarrival-time", "1"],
+        ];
+        for args in settings {
+            let generator = AlpGenerator::from_args(args);
+            for seed in 0..20 {
+                let instance = generator.build_seeded(seed);
+                assert!(instance.validate().is_empty(), "{args:?} seed {seed}");
+                assert!(instance.is_feasible(), "{args:?} seed {seed}");
+                assert!(instance.latest.iter().all(|latest| *latest <= instance.horizon()), "{args:?} seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn forced_infeasible_instances_are_infeasible_and_survive_a_json_round_trip() {
+        let generator = AlpGenerator::from_args(&["-n", "20", "-r", "2", "-k", "2", "--wake-model", "--force-infeasible"]);
+        for seed in 0..10 {
+            let instance = generator.build_instance(&mut generator.rng(seed));
+            assert_eq!(instance.expected_feasible, Some(false), "seed {seed}");
+            assert!(instance.validate().is_empty(), "seed {seed}");
+            assert!(!instance.is_feasible(), "seed {seed}");
+
+            let text = serde_json::to_string(&instance).unwrap();
+            let parsed = serde_json::from_str::<AlpInstance>(&text).unwrap();
+            assert_eq!(parsed, instance, "seed {seed}");
+            assert!(!parsed.is_feasible(), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn generated_targets_are_nondecreasing() {
+        for arrival_model in ["exponential", "uniform-grid", "bimodal-peak"] {
+            for round_mode in ["nearest", "floor", "ceil"] {
+                let generator = AlpGenerator::from_args(&["-n", "50", "-r", "2", "--avg-interarrival-time", "1", "--arrival-model", arrival_model, "--round-mode", round_mode]);
+                for seed in 0..10 {
+                    let target = generator.build_seeded(seed).target;
+                    assert!(target.windows(2).all(|w| w[0] <= w[1]), "{arrival_model} {round_mode} seed {seed}: {target:?}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::{fs::File, io::{BufWriter, Write}, process};
+
+use clap::Args;
+
+use crate::instance::{InstanceFormat, or_exit, read_instance};
+
+#[derive(Debug, Args)]
+pub struct Inspect {
+    /// The path to the instance file (`-` for the standard input)
+    #[clap(short, long)]
+    pub instance: String,
+    /// The format of the instance file
+    #[clap(short, long, value_enum, default_value="json")]
+    pub format: InstanceFormat,
+    /// The number of runways used when the instance format does not specify it
+    #[clap(short='r', long, default_value="1")]
+    pub nb_runways: usize,
+    /// If present, the path where to write the separation matrix as a PPM heatmap
+    #[clap(long)]
+    pub separation_heatmap: Option<String>,
+    /// The runway whose separation matrix is drawn when it depends on the runway
+    #[clap(long, default_value="0")]
+    pub runway: usize,
+    /// The size in pixels of the square drawn for each pair of classes
+    #[clap(long, default_value="16")]
+    pub cell_size: usize,
+}
+
+impl Inspect {
+    pub fn inspect(&self) {
+        let instance = read_instance(&self.instance, self.format, self.nb_runways).unwrap_or_else(|e| {
+            eprintln!("{}: {e}", self.instance);
+            process::exit(1);
+        });
+
+        let errors = instance.validate();
+        if !errors.is_empty() {
+            for error in errors.iter() {
+                eprintln!("{}: {error}", self.instance);
+            }
+            process::exit(1);
+        }
+
+        if self.runway >= instance.nb_runways.max(1) {
+            eprintln!("{}: runway {} does not exist", self.instance, self.runway);
+            process::exit(1);
+        }
+
+        if let Some(path) = self.separation_heatmap.as_ref() {
+            let separation = instance.separation.on_runway(self.runway);
+            let mut out = BufWriter::new(or_exit(path, File::create(path)));
+            or_exit(path, self.write_heatmap(&mut out, separation));
+        }
+    }
+
+    /// Writes a binary PPM image where the brightness of the cell `(i, j)` encodes
+    /// `separation[i][j]`, normalized between the smallest and largest separation times
+    fn write_heatmap(&self, out: &mut impl Write, separation: &[Vec<isize>]) -> std::io::Result<()> {
+        let n = separation.len();
+        let min = separation.iter().flatten().copied().min().unwrap_or(0);
+        let max = separation.iter().flatten().copied().max().unwrap_or(0);
+        let range = (max - min).max(1);
+
+        let size = n * self.cell_size;
+        write!(out, "P6\n{size} {size}\n255\n")?;
+
+        for i in 0..size {
+            for j in 0..size {
+                let sep = separation[i / self.cell_size][j / self.cell_size];
+                let level = (255 * (sep - min) / range) as u8;
+                out.write_all(&[level, level, level])?;
+            }
+        }
+
+        out.flush()
+    }
+}
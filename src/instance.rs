@@ -1,8 +1,75 @@
 //! This module defines an abstract representation of a ALP instance.
 
+use std::{collections::{BTreeMap, BTreeSet}, fmt::Display, fs::File, io::{self, BufRead, BufReader, Read, Write}, error::Error, path::Path};
+
+use clap::ValueEnum;
+use flate2::{read::MultiGzDecoder, write::GzEncoder, Compression};
 use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub mod orlib;
+
+/// The file formats from which an instance can be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InstanceFormat {
+    /// The JSON serialization of an AlpInstance
+    Json,
+    /// The text format of the OR-Library ALP instances
+    Orlib,
+}
+
+/// Opens the given file for reading, or the standard input when the path is `-`. The input is
+/// decompressed on the fly when it starts with the gzip magic bytes.
+pub fn open_input(path: impl AsRef<Path>) -> io::Result<Box<dyn Read>> {
+    let input: Box<dyn Read> = if path.as_ref() == Path::new("-") {
+        Box::new(io::stdin())
+    } else {
+        Box::new(File::open(path)?)
+    };
+
+    let mut input = BufReader::new(input);
+    if input.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+        Ok(Box::new(MultiGzDecoder::new(input)))
+    } else {
+        Ok(Box::new(input))
+    }
+}
+
+/// The value of the given result, or exits after printing its error prefixed by the given path
+pub fn or_exit<T, E: Display>(path: impl AsRef<Path>, result: Result<T, E>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("{}: {e}", path.as_ref().display());
+        std::process::exit(1);
+    })
+}
+
+/// Creates the given file for writing, compressed with gzip when its name ends with `.gz`
+pub fn create_output(path: impl AsRef<Path>) -> io::Result<Box<dyn Write>> {
+    let file = File::create(path.as_ref())?;
+    if path.as_ref().extension().map_or(false, |ext| ext == "gz") {
+        Ok(Box::new(GzEncoder::new(file, Compression::default())))
+    } else {
+        Ok(Box::new(file))
+    }
+}
+
+/// Reads an instance in the given format, the number of runways is only used for the formats
+/// that do not specify it. The instance is read from the standard input when the path is `-`.
+pub fn read_instance(path: impl AsRef<Path>, format: InstanceFormat, nb_runways: usize) -> Result<AlpInstance, Box<dyn Error>> {
+    let mut input = BufReader::new(open_input(path)?);
+    match format {
+        InstanceFormat::Json => Ok(serde_json::from_reader(input)?),
+        InstanceFormat::Orlib => {
+            let mut text = String::new();
+            input.read_to_string(&mut text)?;
+            Ok(orlib::parse(&text, nb_runways, 1.0)?)
+        },
+    }
+}
+
+/// The length of a day when the instance does not specify it
+pub const DEFAULT_DAY_LENGTH: isize = 1440;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AlpInstance {
     pub nb_classes: usize,
     pub nb_aircrafts: usize,
@@ -10,5 +77,979 @@ pub struct AlpInstance {
     pub classes: Vec<usize>,
     pub target: Vec<isize>,
     pub latest: Vec<isize>,
-    pub separation: Vec<Vec<isize>>,
+    /// The earliest landing time of each aircraft (no constraint when absent)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub earliest: Vec<isize>,
+    /// The time at which each aircraft becomes known, it cannot land before (no constraint when absent)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub appearance: Vec<isize>,
+    /// The cost per unit of time of each aircraft landing before its target (1 when absent)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub earliness_cost: Vec<isize>,
+    /// The cost per unit of time of each aircraft landing after its target (1 when absent)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lateness_cost: Vec<isize>,
+    pub separation: Separation,
+    /// Whether each aircraft can land on each runway, indexed by `[aircraft][runway]` (all when absent)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub eligible_runways: Vec<Vec<bool>>,
+    /// The runway to which each aircraft is assigned beforehand, if any (none when absent)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fixed_runway: Vec<Option<usize>>,
+    /// The `[start, end)` intervals during which each runway is closed (none when absent)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub runway_unavailable: Vec<Vec<(isize, isize)>>,
+    /// The `[start, end)` times of the day during which all runways are closed every day (none
+    /// when absent). The curfew spans midnight when its start is after its end.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_curfew: Option<(isize, isize)>,
+    /// The length of a day, over which the daily curfew repeats (`DEFAULT_DAY_LENGTH` when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub day_length: Option<isize>,
+    /// The end of the planning horizon, which no latest time may exceed (none when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub horizon: Option<isize>,
+    /// The `(from, matrix)` separation regimes sorted by start time: the separation matrix of a
+    /// regime replaces `separation` on all the runways for the landings at or after its start,
+    /// until the next regime starts (none when absent)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub separation_schedule: Vec<(isize, Vec<Vec<isize>>)>,
+    /// The `(a, b)` pairs of aircrafts such that `a` must land no later than `b`, on any runway
+    /// (none when absent). The aircrafts of a class land in the order of their index, so a pair
+    /// of aircrafts of the same class must follow that order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub precedence: Vec<(usize, usize)>,
+    /// The factor by which the original times were multiplied before being rounded (1 when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_scale: Option<f64>,
+    /// The seed used to generate the instance, if it was generated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u128>,
+    /// The name of each class, shown in the reports instead of its index (indices when absent)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub class_names: Vec<String>,
+    /// Whether the instance is known to admit a feasible schedule, if it is known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_feasible: Option<bool>,
+}
+
+/// The minimum separation times between the landings of two aircraft classes, indexed by
+/// `[class_i][class_j]`, either shared by all runways or indexed by `[runway][class_i][class_j]`.
+/// A separation of zero lets two aircrafts land at the same time on the same runway, and a
+/// negative separation is treated as zero.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Separation {
+    Global(Vec<Vec<isize>>),
+    PerRunway(Vec<Vec<Vec<isize>>>),
+}
+
+impl Separation {
+    /// The separation matrix that applies on the given runway
+    pub fn on_runway(&self, runway: usize) -> &Vec<Vec<isize>> {
+        match self {
+            Separation::Global(matrix) => matrix,
+            Separation::PerRunway(matrices) => &matrices[runway],
+        }
+    }
+
+    /// All the separation matrices of the instance
+    pub fn matrices(&self) -> Vec<&Vec<Vec<isize>>> {
+        match self {
+            Separation::Global(matrix) => vec![matrix],
+            Separation::PerRunway(matrices) => matrices.iter().collect(),
+        }
+    }
+
+    fn matrices_mut(&mut self) -> Vec<&mut Vec<Vec<isize>>> {
+        match self {
+            Separation::Global(matrix) => vec![matrix],
+            Separation::PerRunway(matrices) => matrices.iter_mut().collect(),
+        }
+    }
+}
+
+impl AlpInstance {
+    /// All the separation matrices of the instance, including those of the separation regimes
+    fn separation_matrices(&self) -> Vec<&Vec<Vec<isize>>> {
+        let mut matrices = self.separation.matrices();
+        matrices.extend(self.separation_schedule.iter().map(|(_, matrix)| matrix));
+        matrices
+    }
+
+    fn separation_matrices_mut(&mut self) -> Vec<&mut Vec<Vec<isize>>> {
+        let mut matrices = self.separation.matrices_mut();
+        matrices.extend(self.separation_schedule.iter_mut().map(|(_, matrix)| matrix));
+        matrices
+    }
+
+    /// The separation required on the given runway between an aircraft of class `first` and an
+    /// aircraft of class `second` landing at the given time, zero when it is negative
+    pub fn separation_at(&self, runway: usize, first: usize, second: usize, time: isize) -> isize {
+        let matrix = self.separation_schedule.iter().rev()
+            .find(|(from, _)| *from <= time)
+            .map_or(self.separation.on_runway(runway), |(_, matrix)| matrix);
+        matrix[first][second].max(0)
+    }
+
+    /// The earliest time at or after `ready` when an aircraft of class `class` can land on the
+    /// given runway after an aircraft of class `prev_class` landed at `prev_time`. The landing
+    /// must be separated according to the regime in effect at its own time, and the runway must
+    /// be open.
+    pub fn next_separated_landing(&self, runway: usize, prev_time: isize, prev_class: usize, class: usize, ready: isize) -> isize {
+        let mut time = ready;
+        loop {
+            // the first regime in which a separated landing fits before the next regime starts
+            let mut separated = time.max(prev_time + self.separation.on_runway(runway)[prev_class][class].max(0));
+            for (k, (from, _)) in self.separation_schedule.iter().copied().enumerate() {
+                if separated < from {
+                    break;
+                }
+                separated = time.max(from).max(prev_time + self.separation_schedule[k].1[prev_class][class].max(0));
+            }
+
+            let opened = self.next_opening(runway, separated);
+            if opened == time {
+                return time;
+            }
+            time = opened;
+        }
+    }
+
+    /// Whether `separation[i][j] == separation[j][i]` for all classes and all runways
+    pub fn separation_is_symmetric(&self) -> bool {
+        self.separation_matrices().into_iter().all(|matrix| {
+            matrix.iter().enumerate().all(|(i, row)| row.iter().enumerate().all(|(j, sep)| matrix[j][i] == *sep))
+        })
+    }
+
+    /// Whether some separation time is negative
+    pub fn has_negative_separation(&self) -> bool {
+        self.separation_matrices().into_iter().flatten().flatten().any(|sep| *sep < 0)
+    }
+
+    /// Replaces the negative separation times by zero
+    pub fn clamp_separation(&mut self) {
+        for matrix in self.separation_matrices_mut() {
+            matrix.iter_mut().flatten().for_each(|sep| *sep = (*sep).max(0));
+        }
+    }
+
+    /// Makes the separation matrices symmetric by replacing `separation[i][j]` and
+    /// `separation[j][i]` by their maximum
+    pub fn symmetrize(&mut self) {
+        for matrix in self.separation_matrices_mut() {
+            for i in 0..matrix.len() {
+                for j in (i + 1)..matrix.len() {
+                    let sep = matrix[i][j].max(matrix[j][i]);
+                    matrix[i][j] = sep;
+                    matrix[j][i] = sep;
+                }
+            }
+        }
+    }
+
+    /// Multiplies all the times and separation times by the given factor and rounds them, the
+    /// factor is recorded in the instance so that the times can be converted back
+    pub fn scale_times(&mut self, scale: f64) {
+        let round = |t: &mut isize| *t = (*t as f64 * scale).round() as isize;
+
+        self.target.iter_mut().for_each(round);
+        self.latest.iter_mut().for_each(round);
+        self.earliest.iter_mut().for_each(round);
+        self.appearance.iter_mut().for_each(round);
+        for (from, _) in self.separation_schedule.iter_mut() {
+            round(from);
+        }
+        for matrix in self.separation_matrices_mut() {
+            matrix.iter_mut().flatten().for_each(round);
+        }
+        for (start, end) in self.runway_unavailable.iter_mut().flatten() {
+            round(start);
+            round(end);
+        }
+        if let Some((start, end)) = self.daily_curfew.as_mut() {
+            round(start);
+            round(end);
+            // the day must be scaled along with the curfew, even when its length is the default one
+            self.day_length.get_or_insert(DEFAULT_DAY_LENGTH);
+        }
+        if let Some(day_length) = self.day_length.as_mut() {
+            round(day_length);
+        }
+        if let Some(horizon) = self.horizon.as_mut() {
+            round(horizon);
+        }
+
+        self.time_scale = Some(self.time_scale.unwrap_or(1.0) * scale);
+    }
+
+    /// Renumbers the aircrafts so that the aircraft `i` of the result is the aircraft `order[i]`
+    /// of this instance
+    pub fn permute(&self, order: &[usize]) -> AlpInstance {
+        let mut rank = vec![0; order.len()];
+        for (i, aircraft) in order.iter().copied().enumerate() {
+            rank[aircraft] = i;
+        }
+
+        fn apply<T: Clone>(values: &[T], order: &[usize]) -> Vec<T> {
+            if values.is_empty() {
+                vec![]
+            } else {
+                order.iter().map(|a| values[*a].clone()).collect()
+            }
+        }
+
+        AlpInstance {
+            classes: apply(&self.classes, order),
+            target: apply(&self.target, order),
+            latest: apply(&self.latest, order),
+            earliest: apply(&self.earliest, order),
+            appearance: apply(&self.appearance, order),
+            earliness_cost: apply(&self.earliness_cost, order),
+            lateness_cost: apply(&self.lateness_cost, order),
+            eligible_runways: apply(&self.eligible_runways, order),
+            fixed_runway: apply(&self.fixed_runway, order),
+            precedence: self.precedence.iter().map(|(a, b)| (rank[*a], rank[*b])).collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Keeps only the first `nb_aircrafts` aircrafts of this instance, along with the precedences
+    /// between them. Whether the result is feasible is not known beforehand.
+    pub fn prefix(&self, nb_aircrafts: usize) -> AlpInstance {
+        fn take<T: Clone>(values: &[T], n: usize) -> Vec<T> {
+            values.iter().take(n).cloned().collect()
+        }
+
+        AlpInstance {
+            nb_aircrafts,
+            classes: take(&self.classes, nb_aircrafts),
+            target: take(&self.target, nb_aircrafts),
+            latest: take(&self.latest, nb_aircrafts),
+            earliest: take(&self.earliest, nb_aircrafts),
+            appearance: take(&self.appearance, nb_aircrafts),
+            earliness_cost: take(&self.earliness_cost, nb_aircrafts),
+            lateness_cost: take(&self.lateness_cost, nb_aircrafts),
+            eligible_runways: take(&self.eligible_runways, nb_aircrafts),
+            fixed_runway: take(&self.fixed_runway, nb_aircrafts),
+            precedence: self.precedence.iter().copied().filter(|(a, b)| *a < nb_aircrafts && *b < nb_aircrafts).collect(),
+            expected_feasible: None,
+            ..self.clone()
+        }
+    }
+
+    /// Keeps only the first `nb_runways` runways of this instance, along with their eligibility,
+    /// separation matrices and closures. The aircrafts which are assigned beforehand to a removed
+    /// runway, or which can only land on removed runways, make the result invalid.
+    pub fn with_runways(&self, nb_runways: usize) -> AlpInstance {
+        let separation = match &self.separation {
+            Separation::Global(matrix) => Separation::Global(matrix.clone()),
+            Separation::PerRunway(matrices) => Separation::PerRunway(matrices.iter().take(nb_runways).cloned().collect()),
+        };
+
+        AlpInstance {
+            nb_runways,
+            separation,
+            eligible_runways: self.eligible_runways.iter().map(|eligible| eligible.iter().take(nb_runways).copied().collect()).collect(),
+            runway_unavailable: self.runway_unavailable.iter().take(nb_runways).cloned().collect(),
+            expected_feasible: None,
+            ..self.clone()
+        }
+    }
+
+    /// Converts a time or a cost back to the original units of the instance
+    pub fn unscale(&self, value: isize) -> f64 {
+        value as f64 / self.time_scale.unwrap_or(1.0)
+    }
+
+    /// Whether the separation times depend on the runway
+    pub fn per_runway_separation(&self) -> bool {
+        matches!(self.separation, Separation::PerRunway(_))
+    }
+
+    /// Whether the given aircraft is allowed to land on the given runway, given both its
+    /// eligible runways and its fixed runway
+    pub fn is_eligible(&self, aircraft: usize, runway: usize) -> bool {
+        (self.eligible_runways.is_empty() || self.eligible_runways[aircraft][runway])
+            && self.fixed_runway.get(aircraft).copied().flatten().map_or(true, |r| r == runway)
+    }
+
+    /// Whether some aircrafts are restricted to a subset of the runways
+    pub fn has_runway_restrictions(&self) -> bool {
+        self.eligible_runways.iter().any(|eligible| eligible.iter().any(|e| !e))
+            || self.fixed_runway.iter().any(|r| r.is_some())
+    }
+
+    /// Whether some runways are closed at different times than others
+    pub fn has_distinct_closures(&self) -> bool {
+        self.runway_unavailable.windows(2).any(|w| w[0] != w[1])
+    }
+
+    /// The earliest time at or after the given one when the given runway is open
+    pub fn next_opening(&self, runway: usize, mut time: isize) -> isize {
+        loop {
+            let before = time;
+            if let Some(closures) = self.runway_unavailable.get(runway) {
+                while let Some((_, end)) = closures.iter().find(|(start, end)| *start <= time && time < *end) {
+                    time = *end;
+                }
+            }
+            if let Some(end) = self.daily_curfew_end(time) {
+                time = end;
+            }
+            if time == before {
+                return time;
+            }
+        }
+    }
+
+    /// The end of the daily curfew in progress at the given time, if any
+    fn daily_curfew_end(&self, time: isize) -> Option<isize> {
+        self.daily_curfew_at(time).map(|(_, end)| end)
+    }
+
+    /// The `[start, end)` interval of the daily curfew in progress at the given time, if any
+    fn daily_curfew_at(&self, time: isize) -> Option<(isize, isize)> {
+        let (start, end) = self.daily_curfew?;
+        let day_length = self.day_length.unwrap_or(DEFAULT_DAY_LENGTH);
+        let midnight = time - time.rem_euclid(day_length);
+        let time_of_day = time - midnight;
+
+        if start <= end {
+            (start <= time_of_day && time_of_day < end).then_some((midnight + start, midnight + end))
+        } else if time_of_day >= start {
+            Some((midnight + start, midnight + day_length + end))
+        } else if time_of_day < end {
+            Some((midnight - day_length + start, midnight + end))
+        } else {
+            None
+        }
+    }
+
+    /// Tightens the latest time of each aircraft to the horizon, to the latest time of the
+    /// aircrafts it must precede, and to the start of the daily curfew it falls in, until no
+    /// window changes. An aircraft cannot land after these times in any feasible schedule, so
+    /// the optimal solutions are unchanged. The latest times are never moved before the target
+    /// times. Returns the number of tightened windows.
+    pub fn tighten_latest(&mut self) -> usize {
+        let mut tightened = vec![false; self.nb_aircrafts];
+        let horizon = self.horizon();
+        let mut changed = true;
+        while changed {
+            changed = false;
+
+            let tighten = |latest: &mut isize, target: isize, bound: isize| -> bool {
+                let bound = bound.max(target);
+                if bound < *latest {
+                    *latest = bound;
+                    true
+                } else {
+                    false
+                }
+            };
+
+            for aircraft in 0..self.nb_aircrafts {
+                if tighten(&mut self.latest[aircraft], self.target[aircraft], horizon) {
+                    tightened[aircraft] = true;
+                    changed = true;
+                }
+            }
+            for (before, after) in self.precedence.iter().copied() {
+                if tighten(&mut self.latest[before], self.target[before], self.latest[after]) {
+                    tightened[before] = true;
+                    changed = true;
+                }
+            }
+            for aircraft in 0..self.nb_aircrafts {
+                if let Some((start, _)) = self.daily_curfew_at(self.latest[aircraft]) {
+                    if tighten(&mut self.latest[aircraft], self.target[aircraft], start - 1) {
+                        tightened[aircraft] = true;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        tightened.into_iter().filter(|t| *t).count()
+    }
+
+    /// The earliest time at which the given aircraft can land given its earliest and appearance times
+    pub fn release_time(&self, aircraft: usize) -> isize {
+        let earliest = self.earliest.get(aircraft).copied().unwrap_or(isize::MIN);
+        let appearance = self.appearance.get(aircraft).copied().unwrap_or(isize::MIN);
+        earliest.max(appearance)
+    }
+
+    /// The arrival times obtained by landing the aircrafts in the order of their index, each on
+    /// the eligible runway where it can land the soonest, regardless of the latest times
+    /// (`isize::MAX` for an aircraft without any eligible runway)
+    pub fn greedy_arrivals(&self) -> Vec<isize> {
+        let mut prev: Vec<Option<(isize, usize)>> = vec![None; self.nb_runways];
+        let mut arrivals = Vec::with_capacity(self.nb_aircrafts);
+
+        for aircraft in 0..self.nb_aircrafts {
+            let class = self.classes[aircraft];
+            // an aircraft that must land after one with a larger index cannot be landed greedily
+            let earliest = self.precedence.iter()
+                .filter(|(_, b)| *b == aircraft)
+                .fold(self.release_time(aircraft), |t, (a, _)| t.max(arrivals.get(*a).copied().unwrap_or(isize::MAX)));
+            if earliest == isize::MAX {
+                arrivals.push(isize::MAX);
+                continue;
+            }
+
+            let best = (0..self.nb_runways)
+                .filter(|r| self.is_eligible(aircraft, *r))
+                .map(|r| {
+                    let ready = self.target[aircraft].max(earliest);
+                    let arrival = match prev[r] {
+                        Some((time, prev_class)) => self.next_separated_landing(r, time, prev_class, class, ready),
+                        None => self.next_opening(r, ready),
+                    };
+                    (arrival, r)
+                })
+                .min();
+
+            match best {
+                Some((arrival, runway)) => {
+                    prev[runway] = Some((arrival, class));
+                    arrivals.push(arrival);
+                },
+                None => arrivals.push(isize::MAX),
+            }
+        }
+
+        arrivals
+    }
+
+    /// Whether the greedy schedule of `greedy_arrivals` meets all the latest times. This is a
+    /// sufficient condition for the instance to be feasible, but not a necessary one
+    pub fn is_feasible(&self) -> bool {
+        self.greedy_arrivals().iter().zip(self.latest.iter()).all(|(arrival, latest)| arrival <= latest)
+    }
+}
+
+impl AlpInstance {
+    /// The name of the given class, or its index when the classes are not named
+    pub fn class_name(&self, class: usize) -> String {
+        self.class_names.get(class).cloned().unwrap_or_else(|| class.to_string())
+    }
+
+    /// The number of aircrafts of each class
+    pub fn class_histogram(&self) -> Vec<usize> {
+        let mut histogram = vec![0; self.nb_classes];
+        for class in self.classes.iter().copied() {
+            histogram[class] += 1;
+        }
+        histogram
+    }
+
+    /// The smallest class whose separations from and to every class are those of the given class,
+    /// on every runway and in every separation regime, the negative separations counting as zero
+    pub fn equivalent_class(&self, class: usize) -> usize {
+        let matrices = self.separation_matrices();
+        let same = |a: isize, b: isize| a.max(0) == b.max(0);
+        (0..class)
+            .find(|other| matrices.iter().all(|matrix| {
+                matrix[*other].iter().zip(matrix[class].iter()).all(|(a, b)| same(*a, *b))
+                    && matrix.iter().all(|row| same(row[*other], row[class]))
+            }))
+            .unwrap_or(class)
+    }
+
+    /// The groups of at least two aircrafts which are interchangeable: they belong to equivalent
+    /// classes, share all their times, costs and runways, and are not involved in any precedence.
+    /// The model lands the aircrafts of a class in the order of their index, and records the
+    /// landings of equivalent classes under the same class, so the permutations of a group lead
+    /// to the same states.
+    pub fn duplicate_groups(&self) -> Vec<Vec<usize>> {
+        let constrained = self.precedence.iter().flat_map(|(a, b)| [*a, *b]).collect::<BTreeSet<usize>>();
+
+        let mut groups = BTreeMap::new();
+        for aircraft in (0..self.nb_aircrafts).filter(|a| !constrained.contains(a)) {
+            let key = (
+                self.equivalent_class(self.classes[aircraft]),
+                self.target[aircraft],
+                self.latest[aircraft],
+                self.release_time(aircraft),
+                self.earliness_cost.get(aircraft).copied(),
+                self.lateness_cost.get(aircraft).copied(),
+                (0..self.nb_runways).map(|r| self.is_eligible(aircraft, r)).collect::<Vec<bool>>(),
+            );
+            groups.entry(key).or_insert_with(Vec::new).push(aircraft);
+        }
+
+        groups.into_values().filter(|group| group.len() > 1).collect()
+    }
+
+    /// The time after which no aircraft lands: the explicit horizon of the instance when it has
+    /// one, otherwise the largest latest time plus the largest separation time
+    pub fn horizon(&self) -> isize {
+        self.horizon.unwrap_or_else(|| {
+            let latest = self.latest.iter().copied().max().unwrap_or(0);
+            let separation = self.separation_matrices().into_iter().flatten().flatten().copied().max().unwrap_or(0);
+            latest.saturating_add(separation.max(0))
+        })
+    }
+
+    /// The smallest target time and the largest latest time, if there is any aircraft
+    pub fn time_horizon(&self) -> Option<(isize, isize)> {
+        let start = self.target.iter().copied().min()?;
+        let end = self.latest.iter().copied().max()?;
+        Some((start, end))
+    }
+
+    /// The mean and the max separation time over all the pairs of classes and all the runways,
+    /// if there is any class
+    pub fn separation_stats(&self) -> Option<(f64, isize)> {
+        let times = self.separation.matrices().into_iter()
+            .flat_map(|matrix| matrix.iter().flatten().copied())
+            .collect::<Vec<isize>>();
+        let max = times.iter().copied().max()?;
+        Some((times.iter().sum::<isize>() as f64 / times.len() as f64, max))
+    }
+
+    /// The average slack `latest - target` of the aircrafts of each class, if it has any aircraft
+    pub fn average_slack_per_class(&self) -> Vec<Option<f64>> {
+        let mut total = vec![0; self.nb_classes];
+        for aircraft in 0..self.nb_aircrafts {
+            total[self.classes[aircraft]] += self.latest[aircraft] - self.target[aircraft];
+        }
+
+        self.class_histogram().into_iter().zip(total)
+            .map(|(count, total)| if count == 0 { None } else { Some(total as f64 / count as f64) })
+            .collect()
+    }
+}
+
+/// A structural problem found in an instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstanceError {
+    /// A vector does not have the expected length
+    Length { field: String, expected: usize, actual: usize },
+    /// A separation regime does not start after the previous one
+    UnsortedSeparationSchedule { regime: usize, from: isize, previous: isize },
+    /// An aircraft belongs to a class that does not exist
+    ClassOutOfRange { aircraft: usize, class: usize },
+    /// The time window of an aircraft ends before its target time
+    LatestBeforeTarget { aircraft: usize, target: isize, latest: isize },
+    /// The time window of an aircraft starts after its target time
+    EarliestAfterTarget { aircraft: usize, earliest: isize, target: isize },
+    /// An aircraft appears after its target time
+    AppearanceAfterTarget { aircraft: usize, appearance: isize, target: isize },
+    /// An earliness or lateness cost is negative
+    NegativeCost { field: &'static str, aircraft: usize, cost: isize },
+    /// An aircraft is not allowed to land on any runway
+    NoEligibleRunway { aircraft: usize },
+    /// An aircraft is assigned beforehand to a runway which does not exist or on which it is not allowed to land
+    InvalidFixedRunway { aircraft: usize, runway: usize },
+    /// A closure interval of a runway is empty
+    EmptyClosure { runway: usize, start: isize, end: isize },
+    /// The length of a day is not positive
+    NonPositiveDayLength { day_length: isize },
+    /// The daily curfew is not within a day, or it lasts the whole day
+    InvalidDailyCurfew { start: isize, end: isize },
+    /// The time window of an aircraft lies within the daily curfew
+    WindowInCurfew { aircraft: usize, release: isize, latest: isize },
+    /// The time window of an aircraft ends after the planning horizon
+    LatestAfterHorizon { aircraft: usize, latest: isize, horizon: isize },
+    /// A precedence constraint refers to an aircraft that does not exist
+    PrecedenceOutOfRange { before: usize, after: usize },
+    /// A precedence constraint contradicts the order in which the aircrafts of a class land
+    PrecedenceAgainstClassOrder { before: usize, after: usize },
+    /// The precedence constraints form a cycle through the given aircraft
+    PrecedenceCycle { aircraft: usize },
+}
+
+impl Display for InstanceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstanceError::Length { field, expected, actual } =>
+                write!(f, "{field} has length {actual} instead of {expected}"),
+            InstanceError::UnsortedSeparationSchedule { regime, from, previous } =>
+                write!(f, "separation regime {regime} starts at {from}, not after the previous one starting at {previous}"),
+            InstanceError::ClassOutOfRange { aircraft, class } =>
+                write!(f, "aircraft {aircraft} belongs to class {class} which does not exist"),
+            InstanceError::LatestBeforeTarget { aircraft, target, latest } =>
+                write!(f, "aircraft {aircraft} has latest time {latest} before its target time {target}"),
+            InstanceError::EarliestAfterTarget { aircraft, earliest, target } =>
+                write!(f, "aircraft {aircraft} has earliest time {earliest} after its target time {target}"),
+            InstanceError::AppearanceAfterTarget { aircraft, appearance, target } =>
+                write!(f, "aircraft {aircraft} has appearance time {appearance} after its target time {target}"),
+            InstanceError::NegativeCost { field, aircraft, cost } =>
+                write!(f, "aircraft {aircraft} has negative {field} {cost}"),
+            InstanceError::NoEligibleRunway { aircraft } =>
+                write!(f, "aircraft {aircraft} cannot land on any runway"),
+            InstanceError::InvalidFixedRunway { aircraft, runway } =>
+                write!(f, "aircraft {aircraft} is assigned to runway {runway} on which it cannot land"),
+            InstanceError::EmptyClosure { runway, start, end } =>
+                write!(f, "runway {runway} has an empty closure [{start}, {end})"),
+            InstanceError::NonPositiveDayLength { day_length } =>
+                write!(f, "the length of a day {day_length} is not positive"),
+            InstanceError::InvalidDailyCurfew { start, end } =>
+                write!(f, "the daily curfew [{start}, {end}) is not within a day or lasts the whole day"),
+            InstanceError::WindowInCurfew { aircraft, release, latest } =>
+                write!(f, "aircraft {aircraft} has its time window [{release}, {latest}] within the daily curfew"),
+            InstanceError::LatestAfterHorizon { aircraft, latest, horizon } =>
+                write!(f, "aircraft {aircraft} has latest time {latest} after the horizon {horizon}"),
+            InstanceError::PrecedenceOutOfRange { before, after } =>
+                write!(f, "precedence ({before}, {after}) refers to an aircraft which does not exist"),
+            InstanceError::PrecedenceAgainstClassOrder { before, after } =>
+                write!(f, "precedence ({before}, {after}) contradicts the landing order of the aircrafts of their class"),
+            InstanceError::PrecedenceCycle { aircraft } =>
+                write!(f, "the precedence constraints form a cycle through aircraft {aircraft}"),
+        }
+    }
+}
+
+impl AlpInstance {
+    /// Enumerates all the structural problems of the instance
+    pub fn validate(&self) -> Vec<InstanceError> {
+        let mut errors = vec![];
+
+        let mut check_length = |field: String, expected: usize, actual: usize| {
+            if expected != actual {
+                errors.push(InstanceError::Length { field, expected, actual });
+                false
+            } else {
+                true
+            }
+        };
+
+        let classes_ok = check_length("classes".to_string(), self.nb_aircrafts, self.classes.len());
+        let target_ok = check_length("target".to_string(), self.nb_aircrafts, self.target.len());
+        let latest_ok = check_length("latest".to_string(), self.nb_aircrafts, self.latest.len());
+        let earliest_ok = self.earliest.is_empty() || check_length("earliest".to_string(), self.nb_aircrafts, self.earliest.len());
+        let appearance_ok = self.appearance.is_empty() || check_length("appearance".to_string(), self.nb_aircrafts, self.appearance.len());
+        let earliness_ok = self.earliness_cost.is_empty() || check_length("earliness_cost".to_string(), self.nb_aircrafts, self.earliness_cost.len());
+        let lateness_ok = self.lateness_cost.is_empty() || check_length("lateness_cost".to_string(), self.nb_aircrafts, self.lateness_cost.len());
+
+        let mut eligible_ok = self.eligible_runways.is_empty() || check_length("eligible_runways".to_string(), self.nb_aircrafts, self.eligible_runways.len());
+        for (a, eligible) in self.eligible_runways.iter().enumerate() {
+            eligible_ok &= check_length(format!("eligible_runways[{a}]"), self.nb_runways, eligible.len());
+        }
+
+        let fixed_ok = self.fixed_runway.is_empty() || check_length("fixed_runway".to_string(), self.nb_aircrafts, self.fixed_runway.len());
+
+        if !self.class_names.is_empty() {
+            check_length("class_names".to_string(), self.nb_classes, self.class_names.len());
+        }
+
+        if !self.runway_unavailable.is_empty() {
+            check_length("runway_unavailable".to_string(), self.nb_runways, self.runway_unavailable.len());
+        }
+
+        let matrices = match &self.separation {
+            Separation::Global(matrix) => vec![("separation".to_string(), matrix)],
+            Separation::PerRunway(matrices) => {
+                check_length("separation".to_string(), self.nb_runways, matrices.len());
+                matrices.iter().enumerate().map(|(r, m)| (format!("separation[{r}]"), m)).collect()
+            },
+        };
+        let matrices = matrices.into_iter()
+            .chain(self.separation_schedule.iter().enumerate().map(|(k, (_, m))| (format!("separation_schedule[{k}]"), m)));
+        for (field, matrix) in matrices {
+            check_length(field.clone(), self.nb_classes, matrix.len());
+            for (i, row) in matrix.iter().enumerate() {
+                check_length(format!("{field}[{i}]"), self.nb_classes, row.len());
+            }
+        }
+
+        for (k, pair) in self.separation_schedule.windows(2).enumerate() {
+            if pair[1].0 <= pair[0].0 {
+                errors.push(InstanceError::UnsortedSeparationSchedule { regime: k + 1, from: pair[1].0, previous: pair[0].0 });
+            }
+        }
+
+        if classes_ok {
+            for (aircraft, class) in self.classes.iter().copied().enumerate() {
+                if class >= self.nb_classes {
+                    errors.push(InstanceError::ClassOutOfRange { aircraft, class });
+                }
+            }
+        }
+
+        if target_ok && latest_ok {
+            for aircraft in 0..self.nb_aircrafts {
+                if self.latest[aircraft] < self.target[aircraft] {
+                    errors.push(InstanceError::LatestBeforeTarget { aircraft, target: self.target[aircraft], latest: self.latest[aircraft] });
+                }
+            }
+        }
+
+        if let (true, Some(horizon)) = (latest_ok, self.horizon) {
+            for aircraft in 0..self.nb_aircrafts {
+                if self.latest[aircraft] > horizon {
+                    errors.push(InstanceError::LatestAfterHorizon { aircraft, latest: self.latest[aircraft], horizon });
+                }
+            }
+        }
+
+        let day_length = self.day_length.unwrap_or(DEFAULT_DAY_LENGTH);
+        if day_length <= 0 {
+            errors.push(InstanceError::NonPositiveDayLength { day_length });
+        } else if let Some((start, end)) = self.daily_curfew {
+            // a curfew with the same start and end would close the runways for the whole day
+            if start == end || !(0..day_length).contains(&start) || !(0..day_length).contains(&end) {
+                errors.push(InstanceError::InvalidDailyCurfew { start, end });
+            } else if target_ok && latest_ok {
+                for aircraft in 0..self.nb_aircrafts {
+                    // without earliest times, the aircrafts never land before their target time
+                    let release = if self.earliest.is_empty() {
+                        self.target[aircraft].max(self.release_time(aircraft))
+                    } else {
+                        self.release_time(aircraft)
+                    };
+                    if self.daily_curfew_end(release).map_or(false, |end| end > self.latest[aircraft]) {
+                        errors.push(InstanceError::WindowInCurfew { aircraft, release, latest: self.latest[aircraft] });
+                    }
+                }
+            }
+        }
+
+        if target_ok && earliest_ok {
+            for (aircraft, earliest) in self.earliest.iter().copied().enumerate() {
+                if earliest > self.target[aircraft] {
+                    errors.push(InstanceError::EarliestAfterTarget { aircraft, earliest, target: self.target[aircraft] });
+                }
+            }
+        }
+
+        if target_ok && appearance_ok {
+            for (aircraft, appearance) in self.appearance.iter().copied().enumerate() {
+                if appearance > self.target[aircraft] {
+                    errors.push(InstanceError::AppearanceAfterTarget { aircraft, appearance, target: self.target[aircraft] });
+                }
+            }
+        }
+
+        for (field, costs, ok) in [("earliness_cost", &self.earliness_cost, earliness_ok), ("lateness_cost", &self.lateness_cost, lateness_ok)] {
+            if ok {
+                for (aircraft, cost) in costs.iter().copied().enumerate() {
+                    if cost < 0 {
+                        errors.push(InstanceError::NegativeCost { field, aircraft, cost });
+                    }
+                }
+            }
+        }
+
+        if eligible_ok {
+            for (aircraft, eligible) in self.eligible_runways.iter().enumerate() {
+                if !eligible.iter().any(|e| *e) {
+                    errors.push(InstanceError::NoEligibleRunway { aircraft });
+                }
+            }
+        }
+
+        if fixed_ok && eligible_ok {
+            for (aircraft, runway) in self.fixed_runway.iter().enumerate() {
+                if let Some(runway) = runway.as_ref().copied() {
+                    if runway >= self.nb_runways || (!self.eligible_runways.is_empty() && !self.eligible_runways[aircraft][runway]) {
+                        errors.push(InstanceError::InvalidFixedRunway { aircraft, runway });
+                    }
+                }
+            }
+        }
+
+        for (runway, closures) in self.runway_unavailable.iter().enumerate() {
+            for (start, end) in closures.iter().copied() {
+                if start >= end {
+                    errors.push(InstanceError::EmptyClosure { runway, start, end });
+                }
+            }
+        }
+
+        let mut precedence_ok = true;
+        for (before, after) in self.precedence.iter().copied() {
+            if before >= self.nb_aircrafts || after >= self.nb_aircrafts {
+                errors.push(InstanceError::PrecedenceOutOfRange { before, after });
+                precedence_ok = false;
+            } else if classes_ok && self.classes[before] == self.classes[after] && before > after {
+                errors.push(InstanceError::PrecedenceAgainstClassOrder { before, after });
+            }
+        }
+
+        // the cycle search follows the order of the aircrafts of each class
+        if precedence_ok && classes_ok && self.classes.iter().all(|c| *c < self.nb_classes) {
+            if let Some(aircraft) = self.precedence_cycle() {
+                errors.push(InstanceError::PrecedenceCycle { aircraft });
+            }
+        }
+
+        errors
+    }
+
+    /// An aircraft on a cycle of precedence constraints, including the order of the aircrafts of
+    /// each class, if any, found by repeatedly removing the aircrafts without remaining predecessors
+    fn precedence_cycle(&self) -> Option<usize> {
+        let mut nb_predecessors = vec![0; self.nb_aircrafts];
+        let mut successors = vec![vec![]; self.nb_aircrafts];
+        let mut edges = self.precedence.clone();
+        // the aircrafts of a class land in the order of their index
+        let mut last = vec![None; self.nb_classes];
+        for aircraft in 0..self.nb_aircrafts {
+            if let Some(previous) = last[self.classes[aircraft]].replace(aircraft) {
+                edges.push((previous, aircraft));
+            }
+        }
+        for (before, after) in edges {
+            nb_predecessors[after] += 1;
+            successors[before].push(after);
+        }
+
+        let mut free = (0..self.nb_aircrafts).filter(|a| nb_predecessors[*a] == 0).collect::<Vec<usize>>();
+        while let Some(aircraft) = free.pop() {
+            for next in successors[aircraft].iter().copied() {
+                nb_predecessors[next] -= 1;
+                if nb_predecessors[next] == 0 {
+                    free.push(next);
+                }
+            }
+        }
+
+        nb_predecessors.iter().position(|n| *n > 0)
+    }
+}
+
+#[cfg(test)]
+impl AlpInstance {
+    /// An instance without any of the optional data, whose separation is shared by all runways
+    pub(crate) fn tiny(nb_runways: usize, classes: Vec<usize>, target: Vec<isize>, latest: Vec<isize>, separation: Vec<Vec<isize>>) -> Self {
+        AlpInstance {
+            nb_classes: separation.len(),
+            nb_aircrafts: classes.len(),
+            nb_runways,
+            classes,
+            target,
+            latest,
+            earliest: vec![],
+            appearance: vec![],
+            earliness_cost: vec![],
+            lateness_cost: vec![],
+            separation: Separation::Global(separation),
+            eligible_runways: vec![],
+            fixed_runway: vec![],
+            runway_unavailable: vec![],
+            daily_curfew: None,
+            day_length: None,
+            horizon: None,
+            separation_schedule: vec![],
+            precedence: vec![],
+            time_scale: None,
+            seed: None,
+            class_names: vec![],
+            expected_feasible: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_cycle_through_the_order_of_a_class_is_detected() {
+        // 0 and 2 share a class, so 0 lands before 2 which must land before 1 which must land before 0
+        let mut instance = AlpInstance::tiny(1, vec![0, 1, 0], vec![0, 0, 0], vec![10, 10, 10], vec![vec![1, 1], vec![1, 1]]);
+        instance.precedence = vec![(2, 1), (1, 0)];
+        assert!(instance.validate().iter().any(|e| matches!(e, InstanceError::PrecedenceCycle { .. })));
+
+        instance.precedence = vec![(0, 1), (1, 2)];
+        assert!(instance.validate().is_empty());
+    }
+
+    #[test]
+    fn instances_survive_a_json_round_trip() {
+        let bare = AlpInstance::tiny(2, vec![0, 1, 0], vec![10, 20, 30], vec![40, 50, 60], vec![vec![3, 4], vec![5, 6]]);
+        let text = serde_json::to_string(&bare).unwrap();
+        assert!(!text.contains("earliest") && !text.contains("horizon") && !text.contains("class_names"));
+        assert_eq!(serde_json::from_str::<AlpInstance>(&text).unwrap(), bare);
+
+        let mut full = bare.clone();
+        full.earliest = vec![0, 5, 10];
+        full.appearance = vec![0, 0, 5];
+        full.earliness_cost = vec![1, 2, 3];
+        full.lateness_cost = vec![3, 2, 1];
+        full.eligible_runways = vec![vec![true, false], vec![true, true], vec![false, true]];
+        full.fixed_runway = vec![None, Some(1), None];
+        full.runway_unavailable = vec![vec![(100, 120)], vec![]];
+        full.daily_curfew = Some((1380, 60));
+        full.day_length = Some(1440);
+        full.horizon = Some(1000);
+        full.separation_schedule = vec![(25, vec![vec![1, 2], vec![3, 4]])];
+        full.precedence = vec![(1, 2)];
+        full.time_scale = Some(2.0);
+        full.seed = Some(42);
+        full.class_names = vec!["heavy".to_string(), "light".to_string()];
+        full.expected_feasible = Some(true);
+        let text = serde_json::to_string(&full).unwrap();
+        assert_eq!(serde_json::from_str::<AlpInstance>(&text).unwrap(), full);
+        assert_ne!(full, bare);
+    }
+
+    #[test]
+    fn a_daily_curfew_closes_the_runways_every_day() {
+        // the curfew spans midnight, from 80 to 20 the next day
+        let mut instance = AlpInstance::tiny(1, vec![0, 0, 0, 0], vec![50, 90, 150, 185], vec![60, 130, 160, 230], vec![vec![5]]);
+        instance.day_length = Some(100);
+        instance.daily_curfew = Some((80, 20));
+        assert!(instance.validate().is_empty());
+
+        assert_eq!(instance.next_opening(0, 10), 20);
+        assert_eq!(instance.next_opening(0, 90), 120);
+        assert_eq!(instance.next_opening(0, 150), 150);
+        assert_eq!(instance.next_opening(0, 185), 220);
+        assert_eq!(instance.greedy_arrivals(), vec![50, 120, 150, 220]);
+        assert!(instance.is_feasible());
+
+        // the window of the last aircraft falls within the curfew of the second day
+        instance.latest[3] = 210;
+        assert!(instance.validate().contains(&InstanceError::WindowInCurfew { aircraft: 3, release: 185, latest: 210 }));
+    }
+
+    #[test]
+    fn the_horizon_is_never_before_a_latest_time() {
+        let mut instance = AlpInstance::tiny(2, vec![0, 1, 0], vec![10, 20, 30], vec![50, 40, 90], vec![vec![-3, 6], vec![4, 2]]);
+        assert_eq!(instance.horizon(), 96);
+
+        instance.separation = Separation::Global(vec![vec![-3, -1], vec![-4, -2]]);
+        assert_eq!(instance.horizon(), 90);
+
+        instance.separation_schedule = vec![(60, vec![vec![1, 12], vec![1, 1]])];
+        assert_eq!(instance.horizon(), 102);
+
+        instance.horizon = Some(90);
+        assert!(instance.validate().is_empty());
+        assert_eq!(instance.horizon(), 90);
+
+        instance.horizon = Some(80);
+        assert!(instance.validate().contains(&InstanceError::LatestAfterHorizon { aircraft: 2, latest: 90, horizon: 80 }));
+
+        let instance = AlpInstance::tiny(1, vec![], vec![], vec![], vec![vec![5]]);
+        assert_eq!(instance.horizon(), 5);
+    }
+
+    #[test]
+    fn interchangeable_aircrafts_are_grouped() {
+        let mut instance = AlpInstance::tiny(2, vec![0, 0, 1, 0, 0, 1], vec![10, 10, 10, 10, 20, 10], vec![50; 6], vec![vec![1, 2], vec![2, 1]]);
+        assert_eq!(instance.duplicate_groups(), vec![vec![0, 1, 3], vec![2, 5]]);
+
+        // the two classes become equivalent
+        instance.separation = Separation::Global(vec![vec![1, 1], vec![1, 1]]);
+        assert_eq!(instance.equivalent_class(1), 0);
+        assert_eq!(instance.duplicate_groups(), vec![vec![0, 1, 2, 3, 5]]);
+        instance.separation = Separation::Global(vec![vec![1, 2], vec![2, 1]]);
+        assert_eq!(instance.equivalent_class(1), 1);
+
+        instance.precedence = vec![(3, 5)];
+        assert_eq!(instance.duplicate_groups(), vec![vec![0, 1]]);
+
+        instance.lateness_cost = vec![1, 2, 1, 1, 1, 1];
+        assert!(instance.duplicate_groups().is_empty());
+
+        instance.lateness_cost = vec![];
+        instance.fixed_runway = vec![Some(0), Some(1), None, None, None, None];
+        assert!(instance.duplicate_groups().is_empty());
+    }
 }
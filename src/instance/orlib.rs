@@ -0,0 +1,131 @@
+//! This module parses the ALP instances of the OR-Library (airland1.txt ... airland13.txt).
+//!
+//! The format is the following: the first line gives the number of planes and the
+//! freeze time, then each plane is described by its appearance time, earliest landing
+//! time, target landing time, latest landing time, penalty cost per unit of time for
+//! landing before target and penalty cost per unit of time for landing after target,
+//! followed by the separation times required after that plane lands before each other
+//! plane can land.
+//!
+//! As there is no class data in that format, each plane is treated as its own class.
+//! The penalty costs are rounded to the nearest integer, while the times are multiplied by the
+//! given time scale before being rounded.
+//!
+//! The appearance times are kept, while the freeze time is ignored since the instances are
+//! solved statically.
+
+use std::{fmt::Display, str::FromStr};
+
+use super::{AlpInstance, Separation};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line (1-based) where the error was found, zero if the input ended prematurely
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "unexpected end of input: {}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Iterates over the whitespace separated tokens of the input along with their line number
+struct Tokens<'a> {
+    tokens: Box<dyn Iterator<Item = (usize, &'a str)> + 'a>,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(text: &'a str) -> Self {
+        let tokens = text.lines()
+            .enumerate()
+            .flat_map(|(i, line)| line.split_whitespace().map(move |t| (i + 1, t)));
+
+        Tokens { tokens: Box::new(tokens) }
+    }
+
+    fn next<T: FromStr>(&mut self, what: &str) -> Result<T, ParseError> {
+        match self.tokens.next() {
+            Some((line, token)) => token.parse::<T>().map_err(|_| ParseError {
+                line,
+                message: format!("cannot parse {what} from '{token}'"),
+            }),
+            None => Err(ParseError { line: 0, message: format!("expected {what}") }),
+        }
+    }
+
+    /// Times and costs are sometimes written with decimals, they are rounded to the nearest integer
+    fn next_time(&mut self, what: &str) -> Result<isize, ParseError> {
+        self.next_scaled(what, 1.0)
+    }
+
+    /// Multiplies the next time by the given factor before rounding it
+    fn next_scaled(&mut self, what: &str, scale: f64) -> Result<isize, ParseError> {
+        self.next::<f64>(what).map(|t| (t * scale).round() as isize)
+    }
+}
+
+/// Parses an OR-Library ALP instance to be solved with the given number of runways, its times
+/// being multiplied by the given time scale
+pub fn parse(text: &str, nb_runways: usize, time_scale: f64) -> Result<AlpInstance, ParseError> {
+    let mut tokens = Tokens::new(text);
+
+    let nb_aircrafts = tokens.next::<usize>("number of planes")?;
+    let _freeze_time = tokens.next_time("freeze time")?;
+
+    let mut appearance = vec![];
+    let mut earliest = vec![];
+    let mut target = vec![];
+    let mut latest = vec![];
+    let mut earliness_cost = vec![];
+    let mut lateness_cost = vec![];
+    let mut separation = vec![];
+
+    for i in 0..nb_aircrafts {
+        appearance.push(tokens.next_scaled(&format!("appearance time of plane {i}"), time_scale)?);
+        earliest.push(tokens.next_scaled(&format!("earliest time of plane {i}"), time_scale)?);
+        target.push(tokens.next_scaled(&format!("target time of plane {i}"), time_scale)?);
+        latest.push(tokens.next_scaled(&format!("latest time of plane {i}"), time_scale)?);
+        earliness_cost.push(tokens.next_time(&format!("earliness penalty of plane {i}"))?);
+        lateness_cost.push(tokens.next_time(&format!("lateness penalty of plane {i}"))?);
+
+        let mut row = vec![];
+        for j in 0..nb_aircrafts {
+            row.push(tokens.next_scaled(&format!("separation time between planes {i} and {j}"), time_scale)?);
+        }
+        separation.push(row);
+    }
+
+    Ok(AlpInstance {
+        nb_classes: nb_aircrafts,
+        nb_aircrafts,
+        nb_runways,
+        classes: (0..nb_aircrafts).collect(),
+        target,
+        latest,
+        earliest,
+        appearance,
+        earliness_cost,
+        lateness_cost,
+        separation: Separation::Global(separation),
+        eligible_runways: vec![],
+        fixed_runway: vec![],
+        runway_unavailable: vec![],
+        precedence: vec![],
+        time_scale: if time_scale == 1.0 { None } else { Some(time_scale) },
+        seed: None,
+        class_names: vec![],
+        expected_feasible: None,
+        daily_curfew: None,
+        day_length: None,
+        horizon: None,
+        separation_schedule: vec![],
+    })
+}
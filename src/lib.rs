@@ -0,0 +1,9 @@
+pub mod instance;
+pub mod generate;
+pub mod convert;
+pub mod check;
+pub mod describe;
+pub mod diff;
+pub mod inspect;
+pub mod resolution;
+pub mod verbosity;
@@ -1,29 +1,75 @@
-use clap::{Parser, Subcommand};
-use generate::AlpGenerator;
-use resolution::Solve;
+use std::process;
 
-mod instance;
-mod generate;
-mod resolution;
+use clap::{Parser, Subcommand};
+use alp::check::Check;
+use alp::convert::Convert;
+use alp::describe::Describe;
+use alp::diff::Diff;
+use alp::generate::AlpGenerator;
+use alp::inspect::Inspect;
+use alp::resolution::{Solve, Benchmark, Edit, SeedSweep, record_schemas};
+use alp::verbosity::Verbosity;
 
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
+#[command(arg_required_else_help = true)]
 struct AlpTools {
     #[command(subcommand)]
-    command: Command,
+    command: Option<Command>,
+    /// Print the json schemas of the solution reports and of the solver statistics, then exit
+    #[arg(long)]
+    emit_schema: bool,
+    /// Only print the errors, the results are only written to the requested files
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Print diagnostics to stderr, the progress of the solver is requested with --progress
+    #[arg(short, long, global = true)]
+    verbose: bool,
 }
 
 #[derive(Debug, Subcommand)]
 enum Command {
     Generate(AlpGenerator),
-    Solve(Solve)
+    Convert(Convert),
+    Check(Check),
+    Inspect(Inspect),
+    Describe(Describe),
+    Diff(Diff),
+    Solve(Solve),
+    Benchmark(Benchmark),
+    Edit(Edit),
+    SeedSweep(SeedSweep),
 }
 
 fn main() {
-    let cli = AlpTools::parse();
-    match cli.command {
+    // the exit code 2 is left to the solutions which are not proven optimal
+    let cli = AlpTools::try_parse().unwrap_or_else(|e| {
+        let _ = e.print();
+        process::exit(if e.use_stderr() { 1 } else { 0 });
+    });
+    let verbosity = Verbosity::from_flags(cli.quiet, cli.verbose);
+    if cli.emit_schema {
+        println!("{}", record_schemas());
+        return;
+    }
+    let command = match cli.command {
+        Some(command) => command,
+        None => {
+            eprintln!("a subcommand is required");
+            process::exit(1);
+        },
+    };
+    match command {
         Command::Generate(mut generate) => generate.generate(),
-        Command::Solve(solve) => solve.solve()
+        Command::Convert(convert) => convert.convert(),
+        Command::Check(check) => check.check(),
+        Command::Inspect(inspect) => inspect.inspect(),
+        Command::Describe(describe) => describe.describe(),
+        Command::Diff(diff) => diff.diff(),
+        Command::Solve(solve) => process::exit(solve.solve(verbosity).exit_code()),
+        Command::Benchmark(benchmark) => benchmark.benchmark(verbosity),
+        Command::Edit(edit) => edit.edit(),
+        Command::SeedSweep(sweep) => sweep.sweep(verbosity),
     }
 }
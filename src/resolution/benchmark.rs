@@ -0,0 +1,238 @@
+use std::{fs::{self, File}, io::{self, Write, BufWriter}, path::{Path, PathBuf}, panic::{self, AssertUnwindSafe}, collections::BTreeMap, sync::{mpsc, atomic::{AtomicUsize, Ordering}}, thread};
+
+use clap::Args;
+
+use crate::instance::{or_exit, read_instance, AlpInstance, InstanceFormat};
+use crate::resolution::solve::{solve_instance, RunRecord, SolveOptions, SolveOutcome, FringeType};
+use crate::resolution::width::WidthMode;
+use crate::verbosity::Verbosity;
+
+#[derive(Debug, Args)]
+pub struct Benchmark {
+    /// The directory containing the json instance files, possibly gzipped (or a single instance
+    /// file, or a pattern such as `instances/*.json.gz` where `*` and `?` match any characters
+    /// and any single character of a file or directory name)
+    #[clap(short, long)]
+    pub instances: String,
+    /// max number of nodes in a layer
+    #[clap(short, long, default_value="100")]
+    pub width: usize,
+    /// Whether the max width is the same for all layers or proportional to the number of unassigned aircrafts
+    #[clap(long, value_enum, default_value="fixed")]
+    pub width_mode: WidthMode,
+    /// timeout for each instance
+    #[clap(short, long, default_value="60")]
+    pub timeout: u64,
+    /// The fringe used to select the next sub-problem to explore
+    #[clap(long, value_enum, default_value="nodup-maxub")]
+    pub fringe: FringeType,
+    /// The number of threads used by the solver for each instance
+    #[clap(long, default_value="1")]
+    pub threads: usize,
+    /// The number of instances solved in parallel
+    #[clap(short, long, default_value="1")]
+    pub jobs: usize,
+    /// If present, the path where to write the csv results
+    #[clap(short, long)]
+    pub output: Option<String>,
+    /// If present, the path where to write a json record of each run per line, as soon as it ends
+    #[clap(long)]
+    pub jsonl: Option<String>,
+}
+
+impl Benchmark {
+    /// Solves the instances with a pool of workers. The json records are written as soon as each
+    /// run ends, while the csv rows are written in the order of the instances: the results that
+    /// finish early wait in a map keyed by the index of their instance.
+    pub fn benchmark(&self, verbosity: Verbosity) {
+        let paths = self.instance_paths();
+
+        let mut out: Box<dyn Write> = match self.output.as_ref() {
+            Some(output) => Box::new(BufWriter::new(or_exit(output, File::create(output)))),
+            None => Box::new(io::stdout()),
+        };
+        writeln!(out, "instance,best_value,is_exact,termination,duration,explored,max_fringe_size").unwrap();
+        let mut records = self.jsonl.as_ref().map(|path| BufWriter::new(or_exit(path, File::create(path))));
+        let opts = self.options();
+
+        let mut nb_solved = 0;
+        let mut nb_optimal = 0;
+
+        let next = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..self.jobs.max(1) {
+                let sender = sender.clone();
+                let (next, paths, opts) = (&next, &paths, &opts);
+                scope.spawn(move || loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    if i >= paths.len() {
+                        break;
+                    }
+                    sender.send((i, self.run(&paths[i], opts))).unwrap();
+                });
+            }
+            drop(sender);
+
+            let mut pending = BTreeMap::new();
+            let mut expected = 0;
+            for (i, result) in receiver {
+                if let (Some(records), Ok((instance, outcome))) = (records.as_mut(), result.as_ref()) {
+                    let name = paths[i].file_name().unwrap().to_string_lossy();
+                    let record = RunRecord::new(name.to_string(), instance, &opts, outcome);
+                    writeln!(records, "{}", serde_json::to_string(&record).unwrap()).unwrap();
+                    records.flush().unwrap();
+                }
+                pending.insert(i, result);
+
+                while let Some(result) = pending.remove(&expected) {
+                    let name = paths[expected].file_name().unwrap().to_string_lossy();
+                    expected += 1;
+
+                    let (instance, outcome) = match result {
+                        Ok(result) => result,
+                        Err(e) => {
+                            eprintln!("{name}: {e}");
+                            continue;
+                        },
+                    };
+
+                    let termination = outcome.termination();
+                    let SolveOutcome { cost, is_exact, duration, explored, max_fringe_size, .. } = outcome;
+
+                    let best_value = cost.map(|c| instance.unscale(c).to_string()).unwrap_or_default();
+                    if verbosity == Verbosity::Verbose {
+                        eprintln!("{name}: best value {best_value}, exact {is_exact}, {:.3}s", duration.as_secs_f64());
+                    }
+
+                    writeln!(out, "{},{best_value},{is_exact},{termination},{:.3},{explored},{max_fringe_size}", csv_field(&name), duration.as_secs_f64()).unwrap();
+                    out.flush().unwrap();
+
+                    nb_solved += 1;
+                    if is_exact {
+                        nb_optimal += 1;
+                    }
+                }
+            }
+        });
+
+        if verbosity != Verbosity::Quiet {
+            eprintln!("{nb_optimal}/{} instances proved optimal within the timeout ({} failed)", paths.len(), paths.len() - nb_solved);
+        }
+    }
+
+    fn options(&self) -> SolveOptions {
+        SolveOptions {
+            width: self.width,
+            width_mode: self.width_mode,
+            timeout: self.timeout,
+            fringe: self.fringe,
+            threads: self.threads,
+            ..SolveOptions::default()
+        }
+    }
+
+    /// Reads and solves the given instance, or returns the reason why it failed
+    fn run(&self, path: &Path, opts: &SolveOptions) -> Result<(AlpInstance, SolveOutcome), String> {
+        let instance = read_instance(path, InstanceFormat::Json, 1).map_err(|e| format!("cannot read instance: {e}"))?;
+
+        match panic::catch_unwind(AssertUnwindSafe(|| solve_instance(&instance, opts))) {
+            Ok(Ok(outcome)) => Ok((instance, outcome)),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err("the resolution failed".to_string()),
+        }
+    }
+
+    fn instance_paths(&self) -> Vec<PathBuf> {
+        if self.instances.contains(['*', '?']) {
+            return glob(&self.instances).into_iter().filter(|p| p.is_file()).collect();
+        }
+
+        let path = Path::new(&self.instances);
+        if path.is_file() {
+            return vec![path.to_path_buf()];
+        }
+
+        let mut paths = or_exit(path, fs::read_dir(path))
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.to_str().map_or(false, |p| p.ends_with(".json") || p.ends_with(".json.gz")))
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+        paths
+    }
+}
+
+/// The given text as a csv field, quoted when it contains a separator, a quote or a line break
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Whether the given name matches the given pattern, where `*` matches any sequence of characters
+/// and `?` any single character
+fn matches_pattern(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|i| matches_pattern(rest, &name[i..])),
+        Some(('?', rest)) => !name.is_empty() && matches_pattern(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && matches_pattern(rest, &name[1..]),
+    }
+}
+
+/// The sorted existing paths matching the given pattern, any component of which may contain
+/// wildcards
+fn glob(pattern: &str) -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::new()];
+    for component in Path::new(pattern).components() {
+        let part = component.as_os_str().to_string_lossy();
+        if !part.contains(['*', '?']) {
+            paths.iter_mut().for_each(|p| p.push(component));
+            continue;
+        }
+
+        let part = part.chars().collect::<Vec<char>>();
+        paths = paths.iter()
+            .flat_map(|dir| {
+                let entries = fs::read_dir(if dir.as_os_str().is_empty() { Path::new(".") } else { dir });
+                entries.into_iter().flatten().flatten()
+                    .filter(|entry| matches_pattern(&part, &entry.file_name().to_string_lossy().chars().collect::<Vec<char>>()))
+                    .map(|entry| dir.join(entry.file_name()))
+                    .collect::<Vec<PathBuf>>()
+            })
+            .collect();
+    }
+
+    paths.retain(|p| p.exists());
+    paths.sort();
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, name: &str) -> bool {
+        matches_pattern(&pattern.chars().collect::<Vec<char>>(), &name.chars().collect::<Vec<char>>())
+    }
+
+    #[test]
+    fn wildcards_match_any_characters() {
+        assert!(matches("*.json.gz", "airland1.json.gz"));
+        assert!(matches("airland?.json", "airland1.json"));
+        assert!(matches("*", ""));
+        assert!(!matches("airland?.json", "airland10.json"));
+        assert!(!matches("*.json", "airland1.json.gz"));
+    }
+
+    #[test]
+    fn the_instance_names_with_separators_or_quotes_are_quoted() {
+        assert_eq!(csv_field("airland1.json"), "airland1.json");
+        assert_eq!(csv_field("airland,1.json"), "\"airland,1.json\"");
+        assert_eq!(csv_field("air\"land\"1.json"), "\"air\"\"land\"\"1.json\"");
+        assert_eq!(csv_field("air\nland1.json"), "\"air\nland1.json\"");
+    }
+}
@@ -0,0 +1,264 @@
+//! This module defines the cutoffs which stop the search before it completes.
+
+use std::{fmt::Display, sync::{Mutex, atomic::{AtomicBool, AtomicIsize, AtomicUsize, Ordering}}, time::{Duration, Instant}};
+
+use ddo::{Cutoff, TimeBudget};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// The limit which stopped a search that did not complete
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CutoffKind {
+    /// The timeout expired
+    Time,
+    /// The maximum number of explored nodes was reached
+    Nodes,
+    /// A first solution was found and it was requested to stop there
+    FirstSolution,
+}
+
+/// How a resolution ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Termination {
+    /// The search completed and the best solution is optimal
+    Optimal,
+    /// The search completed without finding any solution
+    Infeasible,
+    /// The timeout expired before the search completed
+    Timeout,
+    /// The maximum number of explored nodes was reached before the search completed
+    MaxNodes,
+    /// The search stopped at the first solution found
+    FirstSolution,
+}
+
+impl Termination {
+    /// The termination of a resolution stopped by the given cutoff, if any
+    pub fn new(found: bool, cutoff: Option<CutoffKind>) -> Self {
+        match cutoff {
+            None if found => Termination::Optimal,
+            None => Termination::Infeasible,
+            Some(CutoffKind::Time) => Termination::Timeout,
+            Some(CutoffKind::Nodes) => Termination::MaxNodes,
+            Some(CutoffKind::FirstSolution) => Termination::FirstSolution,
+        }
+    }
+}
+
+impl Display for Termination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Termination::Optimal => write!(f, "optimal"),
+            Termination::Infeasible => write!(f, "infeasible"),
+            Termination::Timeout => write!(f, "timeout"),
+            Termination::MaxNodes => write!(f, "max-nodes"),
+            Termination::FirstSolution => write!(f, "first-solution"),
+        }
+    }
+}
+
+/// The limit which first stopped the search, shared by the cutoffs wrapping each other so that
+/// the reason of the stop is recorded when it happens
+#[derive(Debug, Default)]
+pub struct Trip(Mutex<Option<CutoffKind>>);
+
+impl Trip {
+    /// Records the given limit unless another one stopped the search before, and returns whether
+    /// the search must stop
+    fn record(&self, kind: Option<CutoffKind>) -> bool {
+        if let Some(kind) = kind {
+            self.0.lock().unwrap().get_or_insert(kind);
+        }
+        kind.is_some()
+    }
+
+    /// The limit which first stopped the search, if any
+    pub fn kind(&self) -> Option<CutoffKind> {
+        *self.0.lock().unwrap()
+    }
+}
+
+/// Stops the search once the time budget is exhausted or once the number of sub-problems
+/// explored, shared with the fringe which counts them, reaches the node budget if any
+pub struct Budget<'a> {
+    time: TimeBudget,
+    max_nodes: Option<usize>,
+    explored: &'a AtomicUsize,
+    trip: &'a Trip,
+}
+
+impl<'a> Budget<'a> {
+    pub fn new(time: Duration, max_nodes: Option<usize>, explored: &'a AtomicUsize, trip: &'a Trip) -> Self {
+        Budget { time: TimeBudget::new(time), max_nodes, explored, trip }
+    }
+
+    /// Whether the node budget is exhausted
+    fn nodes_exhausted(&self) -> bool {
+        self.max_nodes.map_or(false, |max| self.explored.load(Ordering::Relaxed) >= max)
+    }
+}
+
+impl<'a> Cutoff for Budget<'a> {
+    fn must_stop(&self) -> bool {
+        let kind = if self.nodes_exhausted() {
+            Some(CutoffKind::Nodes)
+        } else if self.time.must_stop() {
+            Some(CutoffKind::Time)
+        } else {
+            None
+        };
+        self.trip.record(kind)
+    }
+}
+
+/// The solutions reached by the decision diagrams while the search runs, which the solver does
+/// not expose before it returns. The model offers the value of each solution it reaches and the
+/// improvements over the best value so far are recorded along with the time when they happened.
+#[derive(Debug)]
+pub struct Incumbent {
+    id: usize,
+    start: Instant,
+    found: AtomicBool,
+    best: AtomicIsize,
+    improvements: Mutex<Vec<(Duration, isize)>>,
+}
+
+impl Incumbent {
+    /// An incumbent improving on the given initial value, if any
+    pub fn new(initial: Option<isize>) -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+        Incumbent {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            start: Instant::now(),
+            found: AtomicBool::new(false),
+            best: AtomicIsize::new(initial.unwrap_or(isize::MIN)),
+            improvements: Mutex::new(vec![]),
+        }
+    }
+
+    /// The identifier which tells this incumbent apart from those of other resolutions
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Records a solution of the given value
+    pub fn offer(&self, value: isize) {
+        self.found.store(true, Ordering::Relaxed);
+        if self.best.fetch_max(value, Ordering::Relaxed) < value {
+            self.improvements.lock().unwrap().push((self.start.elapsed(), value));
+        }
+    }
+
+    /// Whether a solution was reached
+    pub fn found(&self) -> bool {
+        self.found.load(Ordering::Relaxed)
+    }
+
+    /// The `(time, value)` improvements recorded from the given one on
+    pub fn improvements_since(&self, from: usize) -> Vec<(Duration, isize)> {
+        self.improvements.lock().unwrap().iter().skip(from).copied().collect()
+    }
+}
+
+/// Stops the search once a first solution is reached, if requested, or once the given cutoff
+/// stops it
+pub struct FirstSolution<'a, C> {
+    pub inner: C,
+    incumbent: &'a Incumbent,
+    enabled: bool,
+    trip: &'a Trip,
+}
+
+impl<'a, C: Cutoff> FirstSolution<'a, C> {
+    pub fn new(inner: C, incumbent: &'a Incumbent, enabled: bool, trip: &'a Trip) -> Self {
+        FirstSolution { inner, incumbent, enabled, trip }
+    }
+
+    /// Whether the search stops because a solution was reached
+    fn reached(&self) -> bool {
+        self.enabled && self.incumbent.found()
+    }
+}
+
+impl<'a, C: Cutoff> Cutoff for FirstSolution<'a, C> {
+    fn must_stop(&self) -> bool {
+        self.trip.record(self.reached().then_some(CutoffKind::FirstSolution)) || self.inner.must_stop()
+    }
+}
+
+/// Prints the improvements of the incumbent, if requested, each time the solver polls the given
+/// cutoff
+pub struct Progress<'a, C> {
+    pub inner: C,
+    incumbent: &'a Incumbent,
+    enabled: bool,
+    printed: AtomicUsize,
+    best: AtomicIsize,
+}
+
+impl<'a, C: Cutoff> Progress<'a, C> {
+    pub fn new(inner: C, incumbent: &'a Incumbent, enabled: bool) -> Self {
+        Progress { inner, incumbent, enabled, printed: AtomicUsize::new(0), best: AtomicIsize::new(isize::MIN) }
+    }
+
+    /// Prints the improvements recorded since the last call
+    pub fn report(&self) {
+        if !self.enabled {
+            return;
+        }
+        let from = self.printed.load(Ordering::Relaxed);
+        let improvements = self.incumbent.improvements_since(from);
+        if improvements.is_empty() || self.printed.compare_exchange(from, from + improvements.len(), Ordering::Relaxed, Ordering::Relaxed).is_err() {
+            return;
+        }
+        for (time, value) in improvements {
+            // the threads may record their improvements out of order
+            if self.best.fetch_max(value, Ordering::Relaxed) < value {
+                eprintln!("[t={:.1}s] new best: {}", time.as_secs_f64(), -value);
+            }
+        }
+    }
+}
+
+impl<'a, C: Cutoff> Cutoff for Progress<'a, C> {
+    fn must_stop(&self) -> bool {
+        self.report();
+        self.inner.must_stop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_cutoffs_record_the_limit_which_first_stopped_the_search() {
+        let explored = AtomicUsize::new(0);
+        let incumbent = Incumbent::new(None);
+        let trip = Trip::default();
+        let cutoff = FirstSolution::new(Budget::new(Duration::from_secs(60), Some(2), &explored, &trip), &incumbent, true, &trip);
+        assert!(!cutoff.must_stop());
+        assert_eq!(trip.kind(), None);
+
+        explored.store(2, Ordering::Relaxed);
+        assert!(cutoff.must_stop());
+        incumbent.offer(-3);
+        assert!(cutoff.must_stop());
+        assert_eq!(trip.kind(), Some(CutoffKind::Nodes));
+    }
+
+    #[test]
+    fn a_solution_reached_before_the_budget_is_exhausted_stops_the_search_at_the_first_solution() {
+        let explored = AtomicUsize::new(0);
+        let incumbent = Incumbent::new(None);
+        let trip = Trip::default();
+        let cutoff = FirstSolution::new(Budget::new(Duration::from_secs(60), Some(1), &explored, &trip), &incumbent, true, &trip);
+        incumbent.offer(-3);
+        assert!(cutoff.must_stop());
+        explored.store(1, Ordering::Relaxed);
+        assert!(cutoff.must_stop());
+        assert_eq!(trip.kind(), Some(CutoffKind::FirstSolution));
+    }
+}
@@ -0,0 +1,213 @@
+//! This module defines an interactive editor to move the aircrafts of a solution and observe
+//! the effect on its cost and feasibility.
+
+use std::{io::{self, BufRead}, process};
+
+use clap::Args;
+use ddo::{Decision, Problem, Variable};
+
+use crate::instance::{InstanceFormat, or_exit, read_instance};
+use crate::resolution::model::{add_cost, Alp, AlpDecision, RunwayState, Objective};
+use crate::resolution::solve::{read_report, validate_solution};
+
+#[derive(Debug, Args)]
+pub struct Edit {
+    /// The path to the instance file (`-` for the standard input)
+    #[clap(short, long)]
+    pub instance: String,
+    /// The format of the instance file
+    #[clap(short, long, value_enum, default_value="json")]
+    pub format: InstanceFormat,
+    /// The number of runways used when the instance format does not specify it
+    #[clap(short='r', long, default_value="1")]
+    pub nb_runways: usize,
+    /// The path to the solution json to edit
+    #[clap(short, long)]
+    pub solution: String,
+    /// The objective used to evaluate the schedule
+    #[clap(long, value_enum, default_value="total-deviation")]
+    pub objective: Objective,
+    /// The factor applied to the earliness cost of all the aircrafts
+    #[clap(long, default_value="1")]
+    pub earliness_weight: usize,
+    /// The factor applied to the lateness cost of all the aircrafts
+    #[clap(long, default_value="1")]
+    pub lateness_weight: usize,
+    /// Accept the moves which make the schedule violate a constraint
+    #[clap(long)]
+    pub allow_infeasible: bool,
+}
+
+impl Edit {
+    /// Reads `move <aircraft> <runway> <position>` commands from the standard input and prints
+    /// the schedule and the change of cost after each accepted move
+    pub fn edit(&self) {
+        let instance = read_instance(&self.instance, self.format, self.nb_runways).unwrap_or_else(|e| {
+            eprintln!("{}: {e}", self.instance);
+            process::exit(1);
+        });
+
+        let errors = instance.validate();
+        if !errors.is_empty() {
+            for error in errors.iter() {
+                eprintln!("{}: {error}", self.instance);
+            }
+            process::exit(1);
+        }
+
+        let report = or_exit(&self.solution, read_report(&self.solution));
+        let solution = report.solution.unwrap_or_else(|| {
+            eprintln!("{}: it does not contain any solution", self.solution);
+            process::exit(1);
+        });
+        if solution.len() != instance.nb_runways {
+            eprintln!("{}: the solution has {} runways instead of {}", self.solution, solution.len(), instance.nb_runways);
+            process::exit(1);
+        }
+
+        let mut problem = Alp::new(instance.clone());
+        problem.objective = self.objective;
+        problem.earliness_weight = self.earliness_weight as isize;
+        problem.lateness_weight = self.lateness_weight as isize;
+
+        let mut sequences = solution.iter()
+            .map(|landings| landings.iter().map(|l| l.aircraft).collect())
+            .collect::<Vec<Vec<usize>>>();
+        let mut cost = match evaluate(&problem, &sequences) {
+            Ok((cost, runways)) => {
+                print_schedule(&problem, &runways);
+                cost
+            },
+            Err(e) => {
+                eprintln!("{}: {e}", self.solution);
+                process::exit(1);
+            },
+        };
+        println!("cost {}", instance.unscale(cost));
+
+        for line in io::stdin().lock().lines() {
+            let line = line.unwrap();
+            let words = line.split_whitespace().collect::<Vec<&str>>();
+            let (aircraft, runway, position) = match words.as_slice() {
+                [] => continue,
+                ["quit"] | ["exit"] => break,
+                ["move", aircraft, runway, position] => match (aircraft.parse::<usize>(), runway.parse::<usize>(), position.parse::<usize>()) {
+                    (Ok(aircraft), Ok(runway), Ok(position)) => (aircraft, runway, position),
+                    _ => {
+                        eprintln!("usage: move <aircraft> <runway> <position>");
+                        continue;
+                    },
+                },
+                _ => {
+                    eprintln!("usage: move <aircraft> <runway> <position>");
+                    continue;
+                },
+            };
+
+            if runway >= instance.nb_runways {
+                eprintln!("runway {runway} does not exist");
+                continue;
+            }
+            let from = match sequences.iter().position(|s| s.contains(&aircraft)) {
+                Some(from) => from,
+                None => {
+                    eprintln!("aircraft {aircraft} is not in the schedule");
+                    continue;
+                },
+            };
+
+            let mut moved = sequences.clone();
+            moved[from].retain(|a| *a != aircraft);
+            let position = position.min(moved[runway].len());
+            moved[runway].insert(position, aircraft);
+
+            let (new_cost, runways) = match evaluate(&problem, &moved) {
+                Ok(result) => result,
+                Err(e) => {
+                    eprintln!("rejected: {e}");
+                    continue;
+                },
+            };
+
+            let violations = validate_solution(&instance, &runways);
+            for violation in violations.iter() {
+                eprintln!("{violation}");
+            }
+            if !violations.is_empty() && !self.allow_infeasible {
+                eprintln!("rejected: the schedule would violate {} constraints", violations.len());
+                continue;
+            }
+
+            print_schedule(&problem, &runways);
+            println!("cost {} (delta {})", instance.unscale(new_cost), instance.unscale(new_cost - cost));
+            sequences = moved;
+            cost = new_cost;
+        }
+    }
+}
+
+/// Lands the aircrafts in the given order on each runway through the model, each as early as
+/// possible, and returns the cost of the schedule along with its `(arrival, aircraft)` landings.
+///
+/// The landings of the runways are interleaved so that the aircrafts of each class land in the
+/// order of their index and after their predecessors, as required by the model. An error is
+/// returned when the orders of the runways make it impossible.
+pub(super) fn evaluate(problem: &Alp, sequences: &[Vec<usize>]) -> Result<(isize, Vec<Vec<(isize, usize)>>), String> {
+    let instance = &problem.instance;
+    let nb_landings = sequences.iter().map(|s| s.len()).sum::<usize>();
+    if nb_landings != instance.nb_aircrafts {
+        return Err(format!("the schedule lands {nb_landings} aircrafts instead of {}", instance.nb_aircrafts));
+    }
+    if let Some(aircraft) = sequences.iter().flatten().find(|a| **a >= instance.nb_aircrafts) {
+        return Err(format!("aircraft {aircraft} does not exist"));
+    }
+
+    // when the runways are symmetric, the runway of a decision is a position in the sorted runway states
+    let mut slots = (0..instance.nb_runways)
+        .map(|r| (RunwayState::default(), r))
+        .collect::<Vec<(RunwayState, usize)>>();
+    let mut state = problem.initial_state();
+    let mut value = problem.initial_value();
+    let mut runways = vec![vec![]; instance.nb_runways];
+    let mut next = vec![0; instance.nb_runways];
+
+    for depth in 0..nb_landings {
+        let runway = (0..instance.nb_runways).find(|r| {
+            sequences[*r].get(next[*r]).map_or(false, |aircraft| {
+                let class = instance.classes[*aircraft];
+                state.rem[class] > 0 && problem.next[class][state.rem[class]] == *aircraft && problem.predecessors_scheduled(&state, *aircraft)
+            })
+        });
+        let runway = match runway {
+            Some(runway) => runway,
+            None => return Err("the aircrafts of a class do not land in the order of their index, or an aircraft lands before one that must precede it".to_string()),
+        };
+
+        let aircraft = sequences[runway][next[runway]];
+        let class = instance.classes[aircraft];
+        let slot = slots.iter().position(|(_, r)| *r == runway).unwrap();
+        let early = problem.prefers_early(&state, aircraft, slot);
+        let arrival = problem.get_arrival_time(&state, aircraft, slot, early);
+
+        let decision = Decision { variable: Variable(depth), value: problem.to_decision(&AlpDecision { class, runway: slot, early }) };
+        value = add_cost(value, problem.transition_cost(&state, decision));
+        state = problem.transition(&state, decision);
+
+        slots[slot].0 = problem.landed(arrival, class);
+        if problem.symmetric_runways {
+            slots.sort_unstable();
+        }
+
+        runways[runway].push((arrival, aircraft));
+        next[runway] += 1;
+    }
+
+    Ok((-value, runways))
+}
+
+fn print_schedule(problem: &Alp, runways: &[Vec<(isize, usize)>]) {
+    for (r, landings) in runways.iter().enumerate() {
+        let landings = landings.iter().map(|(arrival, aircraft)| format!("({}, {aircraft})", problem.instance.unscale(*arrival))).collect::<Vec<String>>();
+        println!("runway {r}: [{}]", landings.join(", "));
+    }
+}
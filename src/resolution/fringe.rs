@@ -0,0 +1,58 @@
+//! This module defines a fringe wrapper which gathers statistics about the search.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ddo::{Fringe, SubProblem};
+
+/// A fringe which delegates to another one while counting the sub-problems popped from
+/// it and recording the maximum number of sub-problems it has held at once. The counter of
+/// popped sub-problems is shared so that it can be read while the solver owns the fringe.
+pub struct StatsFringe<'a, T> {
+    inner: &'a mut (dyn Fringe<State = T> + Send + Sync),
+    /// The number of sub-problems popped from the fringe
+    pub explored: &'a AtomicUsize,
+    /// The peak size of the fringe
+    pub max_size: usize,
+    /// Applied to each sub-problem popped from the fringe, if any
+    revalue: Option<fn(SubProblem<T>) -> SubProblem<T>>,
+}
+
+impl<'a, T> StatsFringe<'a, T> {
+    pub fn new(inner: &'a mut (dyn Fringe<State = T> + Send + Sync), explored: &'a AtomicUsize) -> Self {
+        StatsFringe { inner, explored, max_size: 0, revalue: None }
+    }
+
+    /// Applies the given function to each sub-problem popped from the fringe
+    pub fn revaluing(mut self, revalue: fn(SubProblem<T>) -> SubProblem<T>) -> Self {
+        self.revalue = Some(revalue);
+        self
+    }
+}
+
+impl<'a, T> Fringe for StatsFringe<'a, T> {
+    type State = T;
+
+    fn push(&mut self, node: SubProblem<Self::State>) {
+        self.inner.push(node);
+        self.max_size = self.max_size.max(self.inner.len());
+    }
+
+    fn pop(&mut self) -> Option<SubProblem<Self::State>> {
+        let node = self.inner.pop();
+        if node.is_some() {
+            self.explored.fetch_add(1, Ordering::Relaxed);
+        }
+        match self.revalue {
+            Some(revalue) => node.map(revalue),
+            None => node,
+        }
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear()
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
@@ -0,0 +1,113 @@
+//! This module defines a greedy heuristic which gives a baseline to judge the solver against.
+
+use std::time::Instant;
+
+use ddo::{Decision, Problem, Variable};
+
+use crate::instance::AlpInstance;
+use crate::resolution::model::{add_cost, AlpDecision};
+use crate::resolution::solve::{build_problem, reconstruct, degenerate_outcome, Landing, PhaseTimings, SolveError, SolveOptions, SolveOutcome};
+
+/// Lands the aircrafts one by one through the model, each on the runway where it increases the
+/// objective the least. At each step, the next aircraft is the one with the smallest target
+/// time among the aircrafts the model may land next, that is in the order of the target times
+/// as long as the aircrafts of each class are numbered by increasing target time.
+///
+/// Only the objective, the weights and the order of the aircrafts of the given options are used,
+/// so that the schedule can be replayed as a warm start of the same options. The outcome carries no
+/// bound, and no schedule when the heuristic reaches a state where no aircraft can land before
+/// its latest time.
+pub fn greedy_schedule(instance: &AlpInstance, opts: &SolveOptions) -> Result<SolveOutcome, SolveError> {
+    let errors = instance.validate();
+    if !errors.is_empty() {
+        return Err(SolveError::InvalidInstance(errors));
+    }
+
+    if instance.nb_aircrafts == 0 || instance.nb_runways == 0 {
+        return Ok(degenerate_outcome(instance));
+    }
+
+    let start = Instant::now();
+
+    let (problem, order, _) = build_problem(instance, opts)?;
+
+    let mut state = problem.initial_state();
+    let mut value = problem.initial_value();
+    let mut decisions = Vec::with_capacity(instance.nb_aircrafts);
+
+    for depth in 0..instance.nb_aircrafts {
+        let variable = Variable(depth);
+        let mut domain = vec![];
+        problem.for_each_in_domain(variable, &state, &mut |decision: Decision| domain.push(decision));
+
+        // (target, aircraft, -cost, arrival) of the best landing so far
+        let best = domain.into_iter()
+            .filter(|decision| decision.value != -1)
+            .map(|decision| {
+                let AlpDecision { class, runway, early } = problem.from_decision(decision.value);
+                let aircraft = problem.next[class][state.rem[class]];
+                let arrival = problem.get_arrival_time(&state, aircraft, runway, early);
+                ((instance.target[order[aircraft]], aircraft, -problem.transition_cost(&state, decision), arrival), decision)
+            })
+            .min_by_key(|(key, _)| *key);
+
+        let decision = match best {
+            Some((_, decision)) => decision,
+            None => {
+                return Ok(SolveOutcome {
+                    cost: None,
+                    bound: None,
+                    is_exact: false,
+                    duration: start.elapsed(),
+                    schedule: None,
+                    warm_start_cost: None,
+                    explored: 0,
+                    max_fringe_size: 0,
+                    cutoff: None,
+                    decisions: None,
+                    timings: PhaseTimings { search: start.elapsed().as_secs_f64(), ..PhaseTimings::default() },
+                });
+            },
+        };
+
+        value = add_cost(value, problem.transition_cost(&state, decision));
+        state = problem.transition(&state, decision);
+        decisions.push(decision);
+    }
+
+    let search = start.elapsed();
+    let schedule = reconstruct(&problem, &decisions).into_iter()
+        .map(|landings| landings.into_iter().map(|(arrival, aircraft)| (arrival, order[aircraft])).collect())
+        .collect();
+
+    Ok(SolveOutcome {
+        cost: Some(-value),
+        bound: None,
+        is_exact: false,
+        duration: start.elapsed(),
+        schedule: Some(schedule),
+        warm_start_cost: None,
+        explored: 0,
+        max_fringe_size: 0,
+        cutoff: None,
+        decisions: Some(decisions),
+        timings: PhaseTimings {
+            setup: 0.0,
+            search: search.as_secs_f64(),
+            reconstruction: (start.elapsed() - search).as_secs_f64(),
+        },
+    })
+}
+
+/// The landings of the given schedule, in the format of the warm start solutions
+pub fn to_landings(instance: &AlpInstance, schedule: &[Vec<(isize, usize)>]) -> Vec<Vec<Landing>> {
+    schedule.iter()
+        .map(|landings| landings.iter()
+            .map(|(arrival, aircraft)| Landing {
+                aircraft: *aircraft,
+                class: instance.classes[*aircraft],
+                arrival_time: instance.unscale(*arrival),
+            })
+            .collect())
+        .collect()
+}
@@ -0,0 +1,95 @@
+//! This module defines a local search which polishes the schedule found by the solver when it
+//! could not prove it optimal.
+
+use std::time::{Duration, Instant};
+
+use crate::instance::AlpInstance;
+use crate::resolution::edit::evaluate;
+use crate::resolution::model::Alp;
+use crate::resolution::solve::{build_problem, validate_solution, SolveOptions};
+
+/// The cost and the `(arrival, aircraft)` landings of each runway of a schedule
+type Evaluated = (isize, Vec<Vec<(isize, usize)>>);
+
+/// Improves the given schedule by moving an aircraft to another position, on its runway or on
+/// another one, or by swapping two aircrafts of different runways. The first move which keeps
+/// the schedule feasible and lowers its cost is applied, until no move improves it or the time
+/// limit expires. Each schedule is evaluated by replaying its runway sequences through the model
+/// solved for the given options, whose aircrafts are renumbered as the solver renumbers them.
+///
+/// Returns the cost and the landings of the improved schedule, if any move improved it.
+pub fn polish(instance: &AlpInstance, opts: &SolveOptions, schedule: &[Vec<(isize, usize)>], time_limit: Duration) -> Option<Evaluated> {
+    let deadline = Instant::now() + time_limit;
+
+    let (problem, order, rank) = build_problem(instance, opts).ok()?;
+
+    let mut sequences = schedule.iter()
+        .map(|landings| landings.iter().map(|(_, aircraft)| rank[*aircraft]).collect())
+        .collect::<Vec<Vec<usize>>>();
+    let (initial, _) = evaluate(&problem, &sequences).ok()?;
+
+    let mut best = None;
+    let mut cost = initial;
+    while let Some((moved, evaluated)) = first_improvement(&problem, &sequences, cost, deadline) {
+        sequences = moved;
+        cost = evaluated.0;
+        best = Some(evaluated);
+    }
+
+    best.map(|(cost, runways)| {
+        let runways = runways.into_iter()
+            .map(|landings| landings.into_iter().map(|(arrival, aircraft)| (arrival, order[aircraft])).collect())
+            .collect();
+        (cost, runways)
+    })
+}
+
+/// The first neighbour of the given sequences which is feasible and costs less than the given
+/// cost, if any is found before the deadline
+fn first_improvement(problem: &Alp, sequences: &[Vec<usize>], cost: isize, deadline: Instant) -> Option<(Vec<Vec<usize>>, Evaluated)> {
+    let improves = |neighbour: &[Vec<usize>]| -> Option<Evaluated> {
+        let (new_cost, runways) = evaluate(problem, neighbour).ok()?;
+        (new_cost < cost && validate_solution(&problem.instance, &runways).is_empty()).then_some((new_cost, runways))
+    };
+
+    for from in 0..sequences.len() {
+        for i in 0..sequences[from].len() {
+            // moves of the aircraft to another position
+            for to in 0..sequences.len() {
+                for j in 0..=sequences[to].len() {
+                    if Instant::now() >= deadline {
+                        return None;
+                    }
+                    if from == to && (j == i || j == i + 1) {
+                        continue;
+                    }
+
+                    let mut neighbour = sequences.to_vec();
+                    let aircraft = neighbour[from].remove(i);
+                    let j = if from == to && j > i { j - 1 } else { j };
+                    neighbour[to].insert(j, aircraft);
+                    if let Some(evaluated) = improves(&neighbour) {
+                        return Some((neighbour, evaluated));
+                    }
+                }
+            }
+
+            // swaps with the aircrafts of the next runways
+            for to in (from + 1)..sequences.len() {
+                for j in 0..sequences[to].len() {
+                    if Instant::now() >= deadline {
+                        return None;
+                    }
+
+                    let mut neighbour = sequences.to_vec();
+                    std::mem::swap(&mut neighbour[from][i], &mut neighbour[to][j]);
+                    if let Some(evaluated) = improves(&neighbour) {
+                        return Some((neighbour, evaluated));
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
@@ -1,4 +1,21 @@
 mod solve;
 mod model;
+mod fringe;
+mod width;
+mod render;
+mod benchmark;
+mod cutoff;
+mod greedy;
+mod edit;
+mod sweep;
+mod trace;
+mod local_search;
 
-pub use solve::*;
\ No newline at end of file
+pub use solve::*;
+pub use benchmark::*;
+pub use edit::*;
+pub use sweep::*;
+pub use model::Objective;
+pub use width::{WidthMode, RelaxationMode};
+pub use cutoff::{CutoffKind, Termination};
+pub use greedy::greedy_schedule;
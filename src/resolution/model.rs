@@ -21,85 +21,369 @@
 //! using ddo. It is a fairly simple example but it features most of the aspects you will
 //! want to copy when implementing your own solver.
 
-use std::{vec, collections::HashSet};
+use std::{vec, cell::RefCell, collections::{HashMap, HashSet}, hash::{Hash, Hasher}, mem, sync::Arc};
 
+use clap::ValueEnum;
 use ddo::*;
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
 
 use crate::instance::AlpInstance;
+use crate::resolution::cutoff::Incumbent;
 
 /// The state of the DP model
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone)]
 pub struct AlpState {
     /// The number of remaining aircrafts to schedule for each class
     pub rem: Vec<usize>,
     /// Info about the state of each runway
     pub info: Vec<RunwayState>,
+    /// The largest arrival time or lateness so far (only tracked for the makespan and the
+    /// max lateness objectives)
+    pub running_max: isize,
+    /// The arrival time of each aircraft that must land before another one (`isize::MIN` until
+    /// it is scheduled)
+    pub pred_arrival: Vec<isize>,
+    /// Whether the state descends from a merged state, whose landing times may precede those of
+    /// the states it stands for
+    pub relaxed: bool,
+    /// The value of the path which first reached the state, only used to report the solutions
+    /// found while the search runs. The states are told apart regardless of it, since the states
+    /// reached along several paths share the same future, and the best value of the paths
+    /// reaching a state is tracked apart by `BestValues`.
+    pub value: isize,
+}
+
+impl PartialEq for AlpState {
+    fn eq(&self, other: &Self) -> bool {
+        self.rem == other.rem
+            && self.info == other.info
+            && self.running_max == other.running_max
+            && self.pred_arrival == other.pred_arrival
+            && self.relaxed == other.relaxed
+    }
+}
+
+impl Eq for AlpState {}
+
+impl Hash for AlpState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rem.hash(state);
+        self.info.hash(state);
+        self.running_max.hash(state);
+        self.pred_arrival.hash(state);
+        self.relaxed.hash(state);
+    }
+}
+
+/// The best value of the paths reaching each state of the two layers of a decision diagram
+/// being expanded by a thread. The solver keeps a single state for all the paths reaching it,
+/// the one created by the first path, hence its value may be worse than the value the solver
+/// holds for it.
+#[derive(Default)]
+struct BestValues {
+    /// The identifier of the incumbent of the resolution the layers belong to
+    owner: usize,
+    /// The depth of the layer being expanded
+    depth: usize,
+    current: HashMap<AlpState, isize>,
+    next: HashMap<AlpState, isize>,
+}
+
+impl BestValues {
+    /// Moves to the layer of the given depth of a resolution. The layers of another decision
+    /// diagram are forgotten, since a new diagram starts from a shallower depth.
+    fn expand(&mut self, owner: usize, depth: usize) {
+        if owner == self.owner && depth == self.depth + 1 {
+            self.current = mem::take(&mut self.next);
+        } else if owner != self.owner || depth != self.depth {
+            self.current.clear();
+            self.next.clear();
+        }
+        self.owner = owner;
+        self.depth = depth;
+    }
+}
+
+thread_local! {
+    static BEST_VALUES: RefCell<BestValues> = RefCell::new(BestValues::default());
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Copy)]
+/// Gives the state of the given sub-problem the value the solver holds for it, which may be
+/// better than the value of the path which first reached the state
+pub fn revalue(mut sub: SubProblem<AlpState>) -> SubProblem<AlpState> {
+    if sub.state.value != sub.value {
+        sub.state = Arc::new(AlpState { value: sub.value, ..sub.state.as_ref().clone() });
+    }
+    sub
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Copy, Default)]
 pub struct RunwayState {
-    /// The time of the latest aircraft scheduled
-    pub prev_time: isize,
-    /// The aircraft class scheduled the latest
-    pub prev_class: isize,
+    /// The time of the latest aircraft scheduled, none while the runway is unused
+    pub prev_time: Option<isize>,
+    /// The aircraft class scheduled the latest, none while the runway is unused or when it is
+    /// unknown in a merged state
+    pub prev_class: Option<usize>,
+}
+
+impl RunwayState {
+    /// The state of a runway on which an aircraft of the given class landed at the given time
+    pub fn landed(time: isize, class: usize) -> Self {
+        RunwayState { prev_time: Some(time), prev_class: Some(class) }
+    }
+}
+
+/// The objective function to minimize
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Objective {
+    /// The total weighted deviation of the arrivals from the target times
+    TotalDeviation,
+    /// The arrival time of the last aircraft, measured from time 0
+    Makespan,
+    /// The largest lateness `max(0, arrival - target)` over all the aircrafts
+    MaxLateness,
 }
 
 pub struct AlpDecision {
     pub class: usize,
     pub runway: usize,
+    /// Whether the aircraft lands as early as its earliest time allows rather than at or after
+    /// its target time
+    pub early: bool,
 }
 
 /// This structure describes a ALP instance
 #[derive(Debug, Clone)]
 pub struct Alp {
     pub instance: AlpInstance,
-    pub next: Vec<Vec<usize>>, // The next aircraft to schedule for each class and for each remaining number of aircrafts
-    min_separation_to: Vec<isize>,
+    /// The next aircraft to schedule for each class and for each remaining number of aircrafts.
+    /// Since the decisions pick a class rather than an aircraft, the aircrafts of a class are
+    /// always scheduled in the order of their index, which already rules out the symmetric
+    /// branches between identical aircrafts of a class. The branches between identical aircrafts
+    /// of equivalent classes reach the same states through `representative` instead.
+    pub next: Vec<Vec<usize>>,
+    /// Whether all runways behave the same, in which case the runway states are kept sorted
+    /// to avoid exploring symmetric assignments
+    pub symmetric_runways: bool,
+    pub objective: Objective,
+    /// The global factors of the earliness and lateness costs of all the aircrafts
+    pub earliness_weight: isize,
+    pub lateness_weight: isize,
+    /// Whether the instance gives the earliest times, without which the aircrafts never land
+    /// before their target time
+    pub early_landings: bool,
+    /// Where to report the solutions reached by the decision diagrams, if anywhere
+    pub incumbent: Option<Arc<Incumbent>>,
+    /// The class recorded in the runway states for each class: the smallest class with the same
+    /// separations, so that landing interchangeable aircrafts of equivalent classes in either
+    /// order leads to the same state
+    pub representative: Vec<usize>,
+    min_separation_to: Vec<Vec<isize>>, // The minimum separation to each class for each runway
+    predecessors: Vec<Vec<usize>>, // The aircrafts that must land before each aircraft
+    tracked: Vec<Option<usize>>, // The index in `AlpState::pred_arrival` of each aircraft that must land before another one
+    position: Vec<usize>, // The number of remaining aircrafts of its class when each aircraft is the next one to schedule
 }
 
 impl Alp {
-    pub fn new(instance: AlpInstance) -> Self {
+    pub fn new(mut instance: AlpInstance) -> Self {
+        instance.clamp_separation();
+        let early_landings = !instance.earliest.is_empty();
+        if instance.earliest.is_empty() {
+            instance.earliest = vec![isize::MIN; instance.nb_aircrafts];
+        }
+        if instance.earliness_cost.is_empty() {
+            instance.earliness_cost = vec![1; instance.nb_aircrafts];
+        }
+        if instance.lateness_cost.is_empty() {
+            instance.lateness_cost = vec![1; instance.nb_aircrafts];
+        }
+        // no aircraft lands after the horizon, whether it is explicit or not
+        let horizon = instance.horizon();
+        instance.latest.iter_mut().for_each(|latest| *latest = (*latest).min(horizon));
+
         let mut next = vec![vec![0]; instance.nb_classes];
 
         for i in (0..instance.nb_aircrafts).rev() {
             next[instance.classes[i]].push(i);
         }
 
-        let mut min_separation_to = vec![isize::MAX; instance.nb_classes];
+        let mut position = vec![0; instance.nb_aircrafts];
+        for aircrafts in next.iter() {
+            for (k, aircraft) in aircrafts.iter().copied().enumerate().skip(1) {
+                position[aircraft] = k;
+            }
+        }
 
-        for i in 0..instance.nb_classes {
-            for j in 0..instance.nb_classes {
-                min_separation_to[j] = min_separation_to[j].min(instance.separation[i][j]);
+        let mut predecessors = vec![vec![]; instance.nb_aircrafts];
+        let mut tracked = vec![None; instance.nb_aircrafts];
+        let mut nb_tracked = 0;
+        for (before, after) in instance.precedence.iter().copied() {
+            predecessors[after].push(before);
+            if tracked[before].is_none() {
+                tracked[before] = Some(nb_tracked);
+                nb_tracked += 1;
             }
         }
 
+        let mut min_separation_to = vec![vec![isize::MAX; instance.nb_classes]; instance.nb_runways];
+
+        // the minimum over all the separation regimes keeps the merged states relaxed whatever their landing times
+        for (r, min_separation_to) in min_separation_to.iter_mut().enumerate() {
+            let regimes = instance.separation_schedule.iter().map(|(_, matrix)| matrix);
+            for separation in std::iter::once(instance.separation.on_runway(r)).chain(regimes) {
+                for i in 0..instance.nb_classes {
+                    for j in 0..instance.nb_classes {
+                        min_separation_to[j] = min_separation_to[j].min(separation[i][j]);
+                    }
+                }
+            }
+        }
+
+        let representative = (0..instance.nb_classes).map(|class| instance.equivalent_class(class)).collect();
+
+        let symmetric_runways = !instance.per_runway_separation() && !instance.has_runway_restrictions() && !instance.has_distinct_closures();
+
         Alp {
             instance,
             next,
+            symmetric_runways,
+            objective: Objective::TotalDeviation,
+            earliness_weight: 1,
+            lateness_weight: 1,
+            early_landings,
+            incumbent: None,
+            representative,
             min_separation_to,
+            predecessors,
+            tracked,
+            position,
         }
     }
 
-    pub fn get_arrival_time(&self, info: &Vec<RunwayState>, aircraft: usize, runway: usize) -> isize {
-        if info[runway].prev_time == -1 {
-            self.instance.target[aircraft]
-        } else if info[runway].prev_class == -1 {
-            self.instance.target[aircraft]
-                .max(info[runway].prev_time + self.min_separation_to[self.instance.classes[aircraft]])
+    /// The state of a runway on which an aircraft of the given class landed at the given time
+    pub fn landed(&self, time: isize, class: usize) -> RunwayState {
+        RunwayState::landed(time, self.representative[class])
+    }
+
+    /// The best value known for the given exact state among the paths reaching it in the decision
+    /// diagram being expanded by the current thread
+    fn best_known_value(&self, state: &AlpState) -> isize {
+        BEST_VALUES.with(|values| {
+            let values = values.borrow();
+            values.current.get(state).or_else(|| values.next.get(state)).map_or(state.value, |value| state.value.max(*value))
+        })
+    }
+
+    /// Records the value of the given exact state, reached from a state of the given depth, and
+    /// returns the best value known for it
+    fn record_value(&self, incumbent: &Arc<Incumbent>, depth: usize, state: &AlpState) -> isize {
+        BEST_VALUES.with(|values| {
+            let mut values = values.borrow_mut();
+            values.expand(incumbent.id(), depth);
+            let best = values.next.entry(state.clone()).or_insert(state.value);
+            *best = (*best).max(state.value);
+            *best
+        })
+    }
+
+    /// The number of aircrafts that must land before another one
+    fn nb_tracked(&self) -> usize {
+        self.tracked.iter().flatten().count()
+    }
+
+    /// Whether all the aircrafts that must land before the given one are scheduled. Since the
+    /// aircrafts of a class are scheduled in the order of their index, an aircraft is scheduled
+    /// once fewer aircrafts of its class remain than when it is the next one.
+    pub fn predecessors_scheduled(&self, state: &AlpState, aircraft: usize) -> bool {
+        self.predecessors[aircraft].iter().all(|p| state.rem[self.instance.classes[*p]] < self.position[*p])
+    }
+
+    /// The time before which the given aircraft cannot land: its earliest time in the early mode,
+    /// its target time otherwise or when the instance has no earliest times
+    pub fn ready_time(&self, aircraft: usize, early: bool) -> isize {
+        if early && self.early_landings {
+            self.instance.release_time(aircraft)
         } else {
-            self.instance.target[aircraft]
-                .max(info[runway].prev_time + self.instance.separation[info[runway].prev_class as usize][self.instance.classes[aircraft]])
+            self.instance.target[aircraft].max(self.instance.release_time(aircraft))
+        }
+    }
+
+    /// The arrival time of the given aircraft on the given runway: as early as possible after its
+    /// ready time, the previous landing on the runway and the landings of its predecessors
+    pub fn get_arrival_time(&self, state: &AlpState, aircraft: usize, runway: usize, early: bool) -> isize {
+        let info = &state.info;
+        let class = self.instance.classes[aircraft];
+        let ready = self.predecessors[aircraft].iter()
+            .fold(self.ready_time(aircraft, early), |t, p| t.max(state.pred_arrival[self.tracked[*p].unwrap()]));
+
+        match (info[runway].prev_time, info[runway].prev_class) {
+            (None, _) => self.instance.next_opening(runway, ready),
+            (Some(prev_time), None) => self.instance.next_opening(runway, ready.max(prev_time + self.min_separation_to[runway][class])),
+            (Some(prev_time), Some(prev_class)) => self.instance.next_separated_landing(runway, prev_time, prev_class, class, ready),
+        }
+    }
+
+    /// The quantity whose max over all the aircrafts is minimized by the objective, if it is
+    /// defined by a max
+    pub fn max_term(&self, aircraft: usize, arrival: isize) -> Option<isize> {
+        match self.objective {
+            Objective::TotalDeviation => None,
+            Objective::Makespan => Some(arrival),
+            Objective::MaxLateness => Some((arrival - self.instance.target[aircraft]).max(0)),
+        }
+    }
+
+    /// The weighted deviation from its target of an aircraft landing at the given time, panics
+    /// when it overflows
+    pub fn deviation_cost(&self, aircraft: usize, arrival: isize) -> isize {
+        let target = self.instance.target[aircraft];
+        let earliness = self.earliness_weight.checked_mul(self.instance.earliness_cost[aircraft])
+            .and_then(|c| c.checked_mul(target.checked_sub(arrival)?.max(0)));
+        let lateness = self.lateness_weight.checked_mul(self.instance.lateness_cost[aircraft])
+            .and_then(|c| c.checked_mul(arrival.checked_sub(target)?.max(0)));
+        earliness.zip(lateness).and_then(|(e, l)| e.checked_add(l)).expect("cost overflow")
+    }
+
+    /// The value of the transition landing the given aircraft at the given time from the given state
+    fn landing_value(&self, state: &AlpState, aircraft: usize, arrival: isize) -> isize {
+        match self.objective {
+            // below a merged state, the aircraft may land later, hence closer to its target,
+            // so only its lateness is a safe cost
+            Objective::TotalDeviation if state.relaxed => - self.deviation_cost(aircraft, arrival.max(self.instance.target[aircraft])),
+            Objective::TotalDeviation => - self.deviation_cost(aircraft, arrival),
+            // the objectives defined by a max are decomposed in the increments of the running max
+            Objective::Makespan | Objective::MaxLateness => - (self.max_term(aircraft, arrival).unwrap() - state.running_max).max(0),
+        }
+    }
+
+    /// Whether the given aircraft should land in the early mode on the given runway when only
+    /// its runway is known: the cheaper mode that meets its latest time, on time on ties
+    pub fn prefers_early(&self, state: &AlpState, aircraft: usize, runway: usize) -> bool {
+        let on_time = self.get_arrival_time(state, aircraft, runway, false);
+        let early = self.get_arrival_time(state, aircraft, runway, true);
+        if early == on_time {
+            return false;
+        }
+        if on_time > self.instance.latest[aircraft] {
+            return true;
         }
+
+        self.landing_value(state, aircraft, early) > self.landing_value(state, aircraft, on_time)
     }
 
     pub fn to_decision(&self, decision: &AlpDecision) -> isize {
-        (decision.class + self.instance.nb_classes * decision.runway) as isize
+        let slot = decision.runway + self.instance.nb_runways * decision.early as usize;
+        (decision.class + self.instance.nb_classes * slot) as isize
     }
 
     pub fn from_decision(&self, value: isize) -> AlpDecision {
+        let slot = value as usize / self.instance.nb_classes;
         AlpDecision {
             class: value as usize % self.instance.nb_classes,
-            runway: value as usize / self.instance.nb_classes,
+            runway: slot % self.instance.nb_runways,
+            early: slot >= self.instance.nb_runways,
         }
     }
 }
@@ -120,7 +404,11 @@ impl Problem for Alp {
 
         AlpState {
             rem,
-            info: vec![RunwayState {prev_class: -1, prev_time: -1}; self.instance.nb_runways],
+            info: vec![RunwayState::default(); self.instance.nb_runways],
+            running_max: 0,
+            pred_arrival: vec![isize::MIN; self.nb_tracked()],
+            relaxed: false,
+            value: 0,
         }
     }
 
@@ -132,15 +420,35 @@ impl Problem for Alp {
         if decision.value == -1 {
             state.clone()
         } else {
-            let AlpDecision {class, runway} = self.from_decision(decision.value);
+            let AlpDecision {class, runway, early} = self.from_decision(decision.value);
             let aircraft = self.next[class][state.rem[class]];
 
+            let arrival = self.get_arrival_time(state, aircraft, runway, early);
+
             let mut next = state.clone();
+            // the value of a state is only needed to report the solutions found during the search
+            let value = match self.incumbent.as_ref() {
+                Some(_) if !state.relaxed => self.best_known_value(state),
+                _ => state.value,
+            };
+            next.value = add_cost(value, self.landing_value(state, aircraft, arrival));
             next.rem[self.instance.classes[aircraft]] -= 1;
-            next.info[runway].prev_class = class as isize;
-            next.info[runway].prev_time = self.get_arrival_time(&state.info, aircraft, runway);
+            next.info[runway] = self.landed(arrival, class);
+            if let Some(t) = self.tracked[aircraft] {
+                next.pred_arrival[t] = arrival;
+            }
+
+            if let Some(term) = self.max_term(aircraft, arrival) {
+                next.running_max = next.running_max.max(term);
+            }
+
+            if self.symmetric_runways {
+                next.info.sort_unstable();
+            }
 
-            next.info.sort_unstable();
+            if let (Some(incumbent), false) = (self.incumbent.as_ref(), next.relaxed) {
+                next.value = self.record_value(incumbent, decision.variable.id(), &next);
+            }
             
             next
         }
@@ -150,17 +458,22 @@ impl Problem for Alp {
         if decision.value == -1 {
             0
         } else {
-            let AlpDecision {class, runway} = self.from_decision(decision.value);
+            let AlpDecision {class, runway, early} = self.from_decision(decision.value);
             let aircraft = self.next[class][state.rem[class]];
-            - (self.get_arrival_time(&state.info, aircraft, runway) - self.instance.target[aircraft])
+            let arrival = self.get_arrival_time(state, aircraft, runway, early);
+            self.landing_value(state, aircraft, arrival)
         }
     }
 
-    fn next_variable(&self, depth: usize, _: &mut dyn Iterator<Item = &Self::State>)
+    fn next_variable(&self, depth: usize, next_layer: &mut dyn Iterator<Item = &Self::State>)
         -> Option<ddo::Variable> {
         if depth < self.instance.nb_aircrafts {
             Some(Variable(depth))
         } else {
+            // the exact states of the last layer end the solutions reached by the decision diagram
+            if let Some(incumbent) = self.incumbent.as_ref() {
+                next_layer.filter(|s| !s.relaxed).for_each(|s| incumbent.offer(self.best_known_value(s)));
+            }
             None
         }
     }
@@ -169,18 +482,29 @@ impl Problem for Alp {
         let mut tot_rem = 0;
         let mut used = HashSet::new();
         for (class, rem) in state.rem.iter().copied().enumerate() {
-            if rem > 0 {
+            // the aircrafts that must land before the next one of the class are scheduled first
+            if rem > 0 && self.predecessors_scheduled(state, self.next[class][rem]) {
                 let aircraft = self.next[class][rem];
 
                 used.clear();
                 for runway in 0..self.instance.nb_runways {
-                    if used.contains(&state.info[runway]) {
+                    if self.symmetric_runways && used.contains(&state.info[runway]) {
+                        continue;
+                    }
+                    // the runway restrictions are enforced on the aircraft behind the class-based decision
+                    if !self.instance.is_eligible(aircraft, runway) {
                         continue;
                     }
 
-                    let arrival = self.get_arrival_time(&state.info, aircraft, runway);
-                    if arrival <= self.instance.latest[aircraft] {
-                        f.apply(Decision { variable, value: self.to_decision(&AlpDecision { class, runway }) });
+                    // the early mode is only a distinct decision when it lands the aircraft sooner
+                    let on_time = self.get_arrival_time(state, aircraft, runway, false);
+                    let early = self.get_arrival_time(state, aircraft, runway, true);
+                    if on_time <= self.instance.latest[aircraft] {
+                        f.apply(Decision { variable, value: self.to_decision(&AlpDecision { class, runway, early: false }) });
+                        used.insert(state.info[runway]);
+                    }
+                    if early < on_time && early <= self.instance.latest[aircraft] {
+                        f.apply(Decision { variable, value: self.to_decision(&AlpDecision { class, runway, early: true }) });
                         used.insert(state.info[runway]);
                     }
                 }
@@ -195,15 +519,67 @@ impl Problem for Alp {
     }
 }
 
+/// Adds two costs, panics rather than wrapping around when the sum overflows
+pub fn add_cost(a: isize, b: isize) -> isize {
+    a.checked_add(b).expect("cost overflow")
+}
+
 /// This structure implements the ALP relaxation
 pub struct AlpRelax {
     pb: Alp,
+    /// Whether the bound accounts for the earliest arrival of each remaining aircraft
+    pub chain_bound: bool,
 }
 
 impl AlpRelax {
     pub fn new(pb: Alp) -> Self {
 
-        Self { pb }
+        Self { pb, chain_bound: false }
+    }
+
+    /// The earliest time at which the given aircraft can land on any runway from the given state.
+    /// Since the separation times are nonnegative, the landings of a runway are chronological and
+    /// any later landing of the aircraft on a runway is separated from the last one of the state
+    /// by at least the minimum separation to its class. The precedences are ignored.
+    fn earliest_arrival(&self, state: &AlpState, aircraft: usize) -> Option<isize> {
+        let instance = &self.pb.instance;
+        let class = instance.classes[aircraft];
+        let ready = self.pb.ready_time(aircraft, true);
+
+        (0..instance.nb_runways)
+            .filter(|r| instance.is_eligible(aircraft, *r))
+            .map(|r| {
+                let info = &state.info[r];
+                let arrival = match info.prev_time {
+                    Some(prev_time) => ready.max(prev_time + self.pb.min_separation_to[r][class]),
+                    None => ready,
+                };
+                instance.next_opening(r, arrival)
+            })
+            .min()
+    }
+
+    /// The value of the objective increments left for the remaining aircrafts if each of them
+    /// lands at its earliest arrival. An aircraft whose earliest arrival precedes its target
+    /// time may still land on time, hence its deviation is only counted past its target time.
+    fn chain_upper_bound(&self, state: &AlpState) -> isize {
+        let remaining = state.rem.iter().enumerate()
+            .flat_map(|(class, rem)| self.pb.next[class][1..=*rem].iter().copied());
+
+        let mut total = 0;
+        let mut running_max = state.running_max;
+        for aircraft in remaining {
+            let arrival = match self.earliest_arrival(state, aircraft) {
+                Some(arrival) => arrival,
+                None => continue,
+            };
+            match self.pb.max_term(aircraft, arrival) {
+                Some(term) => running_max = running_max.max(term),
+                None => total = add_cost(total, self.pb.deviation_cost(aircraft, arrival.max(self.pb.instance.target[aircraft]))),
+            }
+        }
+
+        -(total + (running_max - state.running_max))
     }
 }
 
@@ -212,16 +588,26 @@ impl Relaxation for AlpRelax {
 
     fn merge(&self, states: &mut dyn Iterator<Item = &Self::State>) -> Self::State {
         let mut rem = vec![usize::MAX; self.pb.instance.nb_classes];
-        let mut info = vec![RunwayState { prev_class: -1, prev_time: isize::MAX }; self.pb.instance.nb_runways];
+        // an unused runway in any of the states leaves the runway unused in the merged state
+        let mut info = vec![RunwayState { prev_class: None, prev_time: Some(isize::MAX) }; self.pb.instance.nb_runways];
+        let mut running_max = 0;
+        let mut pred_arrival = vec![isize::MAX; self.pb.nb_tracked()];
 
         for s in states {
             rem.iter_mut().enumerate().for_each(|(k,r)| *r = (*r).min(s.rem[k]));
             info.iter_mut().enumerate().for_each(|(r,i)| i.prev_time = i.prev_time.min(s.info[r].prev_time));
+            // the largest running max makes the remaining increments of the objective the smallest
+            running_max = running_max.max(s.running_max);
+            pred_arrival.iter_mut().enumerate().for_each(|(t,a)| *a = (*a).min(s.pred_arrival[t]));
         }
 
         AlpState {
             rem,
             info,
+            running_max,
+            pred_arrival,
+            relaxed: true,
+            value: 0,
         }
     }
 
@@ -236,8 +622,22 @@ impl Relaxation for AlpRelax {
         cost
     }
 
-    fn fast_upper_bound(&self, _: &Self::State) -> isize {
-        0
+    /// As long as the earliness and lateness costs and weights are nonnegative, no transition has a
+    /// positive value and zero is thus a valid bound for all the objectives, regardless of the
+    /// runway closures. With the chain bound, the objective is evaluated as if each remaining
+    /// aircraft landed at its earliest arrival, which the model never undercuts, and without any
+    /// earliness cost since the aircrafts that can land early may as well land on time.
+    ///
+    /// For the makespan and the max lateness, the value of a path is minus the running max
+    /// reached at its end. Merging the states by keeping the largest running max can only
+    /// shrink the remaining increments, hence the merged states never underestimate the value
+    /// of the paths going through them and the relaxation remains sound.
+    fn fast_upper_bound(&self, state: &Self::State) -> isize {
+        if self.chain_bound {
+            self.chain_upper_bound(state).min(0)
+        } else {
+            0
+        }
     }
 }
 
@@ -250,10 +650,159 @@ pub struct AlpRanking;
 impl StateRanking for AlpRanking {
     type State = AlpState;
 
+    /// The states are ranked by the sum of the previous landing times, then the states with the
+    /// fewest remaining aircrafts come first. The remaining ties are broken by comparing the
+    /// states field by field so that the ranking is a total order and the runs are deterministic.
     fn compare(&self, a: &Self::State, b: &Self::State) -> std::cmp::Ordering {
-        let tot_a = a.info.iter().map(|i| i.prev_time).sum::<isize>();
-        let tot_b = b.info.iter().map(|i| i.prev_time).sum::<isize>();
+        let tot_a = a.info.iter().filter_map(|i| i.prev_time).sum::<isize>();
+        let tot_b = b.info.iter().filter_map(|i| i.prev_time).sum::<isize>();
+        let rem_a = a.rem.iter().sum::<usize>();
+        let rem_b = b.rem.iter().sum::<usize>();
         
         tot_a.cmp(&tot_b)
+            .then_with(|| rem_b.cmp(&rem_a))
+            .then_with(|| a.rem.cmp(&b.rem))
+            .then_with(|| a.info.cmp(&b.info))
+            .then_with(|| a.running_max.cmp(&b.running_max))
+            .then_with(|| a.pred_arrival.cmp(&b.pred_arrival))
+            .then_with(|| a.relaxed.cmp(&b.relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use super::*;
+
+    /// The states reachable from the root of the given problem, along with the merges of some pairs
+    fn sample_states(problem: &Alp) -> Vec<AlpState> {
+        let mut states = vec![problem.initial_state()];
+        let mut layer = states.clone();
+        for depth in 0..problem.nb_variables() {
+            let mut next = vec![];
+            for state in layer.iter() {
+                problem.for_each_in_domain(Variable(depth), state, &mut |decision: Decision| next.push(problem.transition(state, decision)));
+            }
+            states.extend(next.iter().cloned());
+            layer = next;
+        }
+
+        let relaxation = AlpRelax::new(problem.clone());
+        let merged = states.windows(2).step_by(3).map(|pair| relaxation.merge(&mut pair.iter())).collect::<Vec<AlpState>>();
+        states.extend(merged);
+        states
+    }
+
+    #[test]
+    fn the_ranking_is_a_total_order() {
+        let mut instance = AlpInstance::tiny(2, vec![0, 1, 0, 1], vec![5, 6, 9, 12], vec![30, 30, 30, 30], vec![vec![3, 5], vec![4, 2]]);
+        instance.earliest = vec![0, 2, 4, 6];
+        instance.precedence = vec![(1, 2)];
+        let problem = Alp::new(instance);
+        let states = sample_states(&problem);
+        let ranking = AlpRanking;
+
+        for a in states.iter() {
+            for b in states.iter() {
+                let ab = ranking.compare(a, b);
+                assert_eq!(ab, ranking.compare(b, a).reverse());
+                assert_eq!(ab == Ordering::Equal, a == b);
+
+                for c in states.iter() {
+                    if ab != Ordering::Greater && ranking.compare(b, c) != Ordering::Greater {
+                        assert_ne!(ranking.compare(a, c), Ordering::Greater);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_landing_at_a_negative_time_is_separated_from_the_next_one() {
+        let mut instance = AlpInstance::tiny(1, vec![0, 0], vec![0, 0], vec![100, 100], vec![vec![5]]);
+        instance.earliest = vec![-1, -1];
+        let problem = Alp::new(instance);
+
+        let root = problem.initial_state();
+        let decision = Decision { variable: Variable(0), value: problem.to_decision(&AlpDecision { class: 0, runway: 0, early: true }) };
+        assert_eq!(problem.get_arrival_time(&root, 0, 0, true), -1);
+
+        let state = problem.transition(&root, decision);
+        assert_eq!(state.info[0], RunwayState::landed(-1, 0));
+        assert_eq!(problem.get_arrival_time(&state, 1, 0, true), 4);
+        assert_eq!(problem.get_arrival_time(&state, 1, 0, false), 4);
+    }
+
+    /// The best value of a complete sequence of decisions from the given exact state, if any
+    fn best_value(problem: &Alp, state: &AlpState) -> Option<isize> {
+        let depth = problem.nb_variables() - state.rem.iter().sum::<usize>();
+        if depth == problem.nb_variables() {
+            return Some(0);
+        }
+
+        let mut domain = vec![];
+        problem.for_each_in_domain(Variable(depth), state, &mut |decision: Decision| domain.push(decision));
+        domain.into_iter()
+            .filter_map(|decision| best_value(problem, &problem.transition(state, decision))
+                .map(|value| value + problem.transition_cost(state, decision)))
+            .max()
+    }
+
+    #[test]
+    fn the_best_value_of_the_paths_reaching_a_solution_is_offered_to_the_incumbent() {
+        let mut instance = AlpInstance::tiny(1, vec![0, 1, 2], vec![10, 10, 100], vec![200; 3], vec![vec![5; 3]; 3]);
+        instance.lateness_cost = vec![1, 2, 1];
+        let mut problem = Alp::new(instance);
+        let incumbent = Arc::new(Incumbent::new(None));
+        problem.incumbent = Some(incumbent.clone());
+
+        let land = |state: &AlpState, depth: usize, class: usize|
+            problem.transition(state, Decision { variable: Variable(depth), value: problem.to_decision(&AlpDecision { class, runway: 0, early: false }) });
+        let root = problem.initial_state();
+        let (a, b) = (land(&root, 0, 0), land(&root, 0, 1));
+        let (ab, ba) = (land(&a, 1, 1), land(&b, 1, 0));
+        let (abc, bac) = (land(&ab, 2, 2), land(&ba, 2, 2));
+
+        // the solver keeps the state of the first path, whose value is worse
+        assert_eq!(abc, bac);
+        assert_eq!(abc.value, -10);
+        problem.next_variable(3, &mut [abc].iter());
+        assert_eq!(incumbent.improvements_since(0).last().map(|(_, value)| *value), Some(-5));
+    }
+
+    #[test]
+    fn the_chain_bound_never_exceeds_the_optimum_of_a_state() {
+        let mut instance = AlpInstance::tiny(2, vec![0, 1, 0, 1, 0], vec![5, 6, 9, 12, 14], vec![40; 5], vec![vec![3, 5], vec![4, 2]]);
+        instance.earliest = vec![0, 2, 4, 6, 8];
+        instance.lateness_cost = vec![2, 1, 3, 1, 2];
+
+        for objective in [Objective::TotalDeviation, Objective::Makespan, Objective::MaxLateness] {
+            let mut problem = Alp::new(instance.clone());
+            problem.objective = objective;
+            let mut relaxation = AlpRelax::new(problem.clone());
+            relaxation.chain_bound = true;
+
+            for state in sample_states(&problem).iter().filter(|state| !state.relaxed) {
+                if let Some(value) = best_value(&problem, state) {
+                    let bound = relaxation.fast_upper_bound(state);
+                    assert!(bound >= value, "{objective:?}: {bound} < {value}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "cost overflow")]
+    fn adding_overflowing_costs_panics() {
+        add_cost(isize::MAX, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "cost overflow")]
+    fn an_overflowing_deviation_panics() {
+        let mut problem = Alp::new(AlpInstance::tiny(1, vec![0], vec![0], vec![isize::MAX], vec![vec![1]]));
+        problem.lateness_weight = isize::MAX;
+        problem.deviation_cost(0, 2);
     }
 }
@@ -0,0 +1,122 @@
+//! This module renders the schedule of the runways as an ASCII Gantt chart, a CSV table or the
+//! graph of the decisions leading to it.
+
+use std::env;
+
+use ddo::{Decision, Problem};
+
+use crate::instance::AlpInstance;
+use crate::resolution::model::{add_cost, Alp, AlpDecision, AlpState, RunwayState};
+
+/// The width of the chart when the terminal width is unknown
+const DEFAULT_COLUMNS: usize = 80;
+
+/// Renders each runway as a timeline on which each landing is marked with the aircraft id, and
+/// the name of its class when the classes are named, at the column corresponding to its arrival
+/// time. When a label does not fit because it collides
+/// with a previous one, a `*` is written at the first free column instead.
+pub fn gantt(instance: &AlpInstance, runways: &[Vec<(isize, usize)>]) -> String {
+    let columns = env::var("COLUMNS").ok().and_then(|c| c.parse::<usize>().ok()).unwrap_or(DEFAULT_COLUMNS);
+    let label_width = format!("runway {} |", runways.len().saturating_sub(1)).len();
+    let width = columns.saturating_sub(label_width).max(10);
+
+    let arrivals = runways.iter().flatten().map(|(arrival, _)| *arrival);
+    let start = instance.target.iter().copied().chain(arrivals.clone()).min().unwrap_or(0);
+    let end = arrivals.max().unwrap_or(start);
+    let span = (end - start).max(1) as f64;
+
+    let column = |time: isize| (((time - start) as f64 / span) * (width - 1) as f64).round() as usize;
+
+    let mut chart = format!("{:>label_width$} {start}..{end}\n", "time");
+    for (r, landings) in runways.iter().enumerate() {
+        let mut line = vec!['-'; width];
+        let mut free = 0;
+        for (arrival, aircraft) in landings.iter().copied() {
+            let col = column(arrival).max(free);
+            let label = if instance.class_names.is_empty() {
+                aircraft.to_string()
+            } else {
+                format!("{aircraft}:{}", instance.class_name(instance.classes[aircraft]))
+            };
+            if col == column(arrival) && col + label.len() <= width {
+                line[col..col + label.len()].iter_mut().zip(label.chars()).for_each(|(c, l)| *c = l);
+                free = col + label.len();
+            } else if col < width {
+                line[col] = '*';
+                free = col + 1;
+            }
+        }
+        chart.push_str(&format!("{:>label_width$}{}\n", format!("runway {r} |"), line.into_iter().collect::<String>()));
+    }
+
+    chart
+}
+
+/// Formats the schedule as a CSV with one row per landing, sorted by runway and arrival time,
+/// with the times in the original units of the instance
+pub fn schedule_csv(instance: &AlpInstance, runways: &[Vec<(isize, usize)>]) -> String {
+    let mut csv = "runway,position_on_runway,aircraft,class,target,latest,arrival,deviation\n".to_string();
+
+    for (r, landings) in runways.iter().enumerate() {
+        let mut landings = landings.clone();
+        landings.sort_unstable();
+
+        for (position, (arrival, aircraft)) in landings.into_iter().enumerate() {
+            let target = instance.target[aircraft];
+            csv.push_str(&format!("{r},{position},{aircraft},{},{},{},{},{}\n",
+                instance.class_name(instance.classes[aircraft]), instance.unscale(target), instance.unscale(instance.latest[aircraft]),
+                instance.unscale(arrival), instance.unscale(arrival - target)));
+        }
+    }
+
+    csv
+}
+
+/// Renders the states along the given decisions as a Graphviz DOT graph. Each node shows the
+/// number of remaining aircrafts of each class, the last landing on each runway, under the first
+/// class with the same separations, and the cost so far, and each edge shows the
+/// `(class, runway, arrival)` of the landing, with the aircraft `order[i]` of the instance
+/// standing for the aircraft `i` of the problem.
+pub fn decision_path_dot(problem: &Alp, decisions: &[Decision], order: &[usize]) -> String {
+    let instance = &problem.instance;
+    let node = |depth: usize, state: &AlpState, value: isize| {
+        let runways = state.info.iter()
+            .map(|info| match (info.prev_class, info.prev_time) {
+                (Some(prev_class), Some(prev_time)) => format!("{}@{}", instance.class_name(prev_class), instance.unscale(prev_time)),
+                _ => "-".to_string(),
+            })
+            .collect::<Vec<String>>();
+        format!("  s{depth} [label=\"rem {:?}\\nrunways [{}]\\ncost {}\"];\n", state.rem, runways.join(", "), instance.unscale(-value))
+    };
+
+    // when the runways are symmetric, the runway of a decision is a position in the sorted runway states
+    let mut slots = (0..instance.nb_runways)
+        .map(|r| (RunwayState::default(), r))
+        .collect::<Vec<(RunwayState, usize)>>();
+    let mut state = problem.initial_state();
+    let mut value = problem.initial_value();
+
+    let mut dot = "digraph solution {\n  node [shape=box];\n".to_string();
+    dot.push_str(&node(0, &state, value));
+
+    for (depth, decision) in decisions.iter().copied().enumerate().filter(|(_, d)| d.value != -1) {
+        let AlpDecision { class, runway, early } = problem.from_decision(decision.value);
+        let aircraft = problem.next[class][state.rem[class]];
+        let arrival = problem.get_arrival_time(&state, aircraft, runway, early);
+
+        dot.push_str(&format!("  s{depth} -> s{} [label=\"aircraft {}: ({}, {}, {})\"];\n",
+            depth + 1, order[aircraft], instance.class_name(class), slots[runway].1, instance.unscale(arrival)));
+
+        slots[runway].0 = problem.landed(arrival, class);
+        if problem.symmetric_runways {
+            slots.sort_unstable();
+        }
+
+        value = add_cost(value, problem.transition_cost(&state, decision));
+        state = problem.transition(&state, decision);
+        dot.push_str(&node(depth + 1, &state, value));
+    }
+
+    dot.push_str("}\n");
+    dot
+}
@@ -1,65 +1,1918 @@
-use std::{fs::File, io::BufReader, time::Duration};
+use std::{collections::HashMap, fs::{self, File}, io::{BufReader, BufWriter}, time::{Duration, Instant}, process, fmt::Display, sync::{Arc, atomic::{AtomicUsize, Ordering}}};
 
 use clap::Args;
-use ddo::{FixedWidth, TimeBudget, NoDupFringe, MaxUB, ParBarrierSolverFc, Completion, Solver, Problem};
+use schemars::{JsonSchema, schema_for};
+use serde::{Serialize, Deserialize};
+use clap::ValueEnum;
+use ddo::{FixedWidth, WidthHeuristic, NoDupFringe, SimpleFringe, MaxUB, ParBarrierSolverFc, Completion, Solver, Problem, Relaxation, StateRanking, Decision, Variable, Fringe};
 
-use crate::resolution::model::{Alp, AlpRelax, AlpRanking, AlpDecision, RunwayState};
-use crate::instance::AlpInstance;
+use crate::resolution::model::{add_cost, revalue, Alp, AlpRelax, AlpRanking, AlpDecision, AlpState, RunwayState, Objective};
+use crate::resolution::fringe::StatsFringe;
+use crate::resolution::cutoff::{Budget, CutoffKind, FirstSolution, Incumbent, Progress, Termination, Trip};
+use crate::resolution::greedy::{greedy_schedule, to_landings};
+use crate::resolution::trace::{write_trace, replay_trace};
+use crate::resolution::local_search::polish;
+use crate::resolution::width::{WidthMode, RelaxationMode, RemainingWidth};
+use crate::resolution::render::{gantt, schedule_csv, decision_path_dot};
+use crate::instance::{AlpInstance, InstanceError, InstanceFormat, or_exit, read_instance};
+use crate::verbosity::Verbosity;
 
+/// The order in which the aircrafts are numbered before solving. Since the aircrafts of a
+/// class are always landed in the order of their index, an order which changes the relative
+/// order of the aircrafts of a class changes the schedules explored by the model, and thus
+/// possibly its optimum, which the solve command warns about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum AircraftOrder {
+    /// The order of the instance
+    None,
+    /// By increasing target time
+    ByTarget,
+    /// By increasing latest time
+    ByLatest,
+}
+
+/// The strategy used to select the next sub-problem to explore
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum FringeType {
+    /// Best-first on the upper bound, merging the sub-problems with the same state: saves
+    /// memory and redundant work at the cost of hashing each state pushed
+    NodupMaxub,
+    /// Best-first on the upper bound without duplicate detection: cheaper operations but the
+    /// same state may be stored and explored several times
+    SimpleMaxub,
+}
+
+/// Solves an instance.
+///
+/// When the instance gives earliest times, each aircraft lands as soon as possible either from
+/// its earliest time or from its target time: the landings strictly between these times are only
+/// considered when the separations delay the early mode, so the optimum is proven over these
+/// schedules only.
+///
+/// Exit codes: 0 when the solution is proven optimal (or when no search is requested), 1 on
+/// errors, 2 when a solution is found but not proven optimal within the limits, and 3 when no
+/// solution is found.
 #[derive(Debug, Args)]
 pub struct Solve {
-    /// The path to the instance file
+    /// The path to the instance file (`-` for the standard input)
     #[clap(short, long)]
     pub instance: String,
+    /// The format of the instance file
+    #[clap(short, long, value_enum, default_value="json")]
+    pub format: InstanceFormat,
+    /// The number of runways used when the instance format does not specify it
+    #[clap(short='r', long, default_value="1")]
+    pub nb_runways: usize,
+    /// The objective function to minimize
+    #[clap(long, value_enum, default_value="total-deviation")]
+    pub objective: Objective,
+    /// The factor applied to the earliness costs of all the aircrafts
+    #[clap(long, default_value="1")]
+    pub earliness_weight: usize,
+    /// The factor applied to the lateness costs of all the aircrafts
+    #[clap(long, default_value="1")]
+    pub lateness_weight: usize,
     /// max number of nodes in a layeer
     #[clap(short, long, default_value="100")]
     pub width: usize,
+    /// Whether the max width is the same for all layers or proportional to the number of unassigned aircrafts
+    #[clap(long, value_enum, default_value="fixed")]
+    pub width_mode: WidthMode,
+    /// Whether the states are merged, which can be disabled to check the relaxation on tiny
+    /// instances: the width is then ignored and the search explores the exact decision diagram
+    #[clap(long, value_enum, default_value="merge")]
+    pub relaxation: RelaxationMode,
+    /// Strengthen the bound of the relaxation with the earliest arrival of each remaining aircraft
+    #[clap(long)]
+    pub chain_bound: bool,
     /// timeout
     #[clap(short, long, default_value="60")]
     pub timeout: u64,
+    /// Stop the search after exploring this many sub-problems, whichever of the timeout and this
+    /// limit is reached first
+    #[clap(long)]
+    pub max_nodes: Option<usize>,
+    /// The fringe used to select the next sub-problem to explore
+    #[clap(long, value_enum, default_value="nodup-maxub")]
+    pub fringe: FringeType,
+    /// The number of threads used by the solver
+    #[clap(long, default_value="1")]
+    pub threads: usize,
+    /// The order in which the aircrafts are renumbered before solving. The aircrafts of a class
+    /// land in the order of their numbers, so an order which changes the relative order of the
+    /// aircrafts of a class may change the optimum found
+    #[clap(long, value_enum, default_value="none")]
+    pub sort_aircraft: AircraftOrder,
+    /// If present, the factor by which the times are multiplied before being rounded, the
+    /// results are reported in the original units
+    #[clap(long)]
+    pub time_scale: Option<f64>,
     /// If present, the path where to write the output html
     #[clap(short, long)]
     pub output: Option<String>,
+    /// If present, the path where to write the solution as json
+    #[clap(long)]
+    pub solution_out: Option<String>,
+    /// If present, the path where to write the schedule as csv
+    #[clap(long)]
+    pub csv_out: Option<String>,
+    /// If present, the path where to write the settings and results of the run as json
+    #[clap(long)]
+    pub solver_stats_out: Option<String>,
+    /// If present, the path where to write the decisions leading to the best solution as a
+    /// Graphviz DOT graph
+    #[clap(long)]
+    pub dot_out: Option<String>,
+    /// If present, the path where to write the decisions leading to the best solution, as json lines
+    #[clap(long)]
+    pub record_trace: Option<String>,
+    /// If present, the path to a trace of decisions which are replayed through the model, without
+    /// solving, to check the cost recorded in the trace
+    #[clap(long)]
+    pub replay_trace: Option<String>,
+    /// If present, the path to a solution json used as initial incumbent
+    #[clap(long)]
+    pub warm_start: Option<String>,
+    /// Only solve the first aircrafts of the instance
+    #[clap(long)]
+    pub limit_aircraft: Option<usize>,
+    /// Make the separation matrix symmetric by taking the max of sep[i][j] and sep[j][i]
+    #[clap(long)]
+    pub symmetrize: bool,
+    /// Tighten the latest times implied by the precedences and the daily curfew before solving
+    #[clap(long)]
+    pub tighten_latest: bool,
+    /// Do not warn when the separation matrix is not symmetric
+    #[clap(long)]
+    pub no_symmetry_warning: bool,
+    /// Print the schedule as an ASCII Gantt chart
+    #[clap(long)]
+    pub gantt: bool,
+    /// Print each improvement of the incumbent to stderr while the search runs
+    #[clap(long)]
+    pub progress: bool,
+    /// Stop as soon as a solution is found, which is then reported as not proven optimal unless
+    /// the search happens to complete
+    #[clap(long)]
+    pub first_solution: bool,
+    /// Print the value and the schedule of a greedy heuristic before solving, the greedy schedule
+    /// is then used as initial incumbent unless a warm start is given
+    #[clap(long)]
+    pub greedy: bool,
+    /// Only run the greedy heuristic, without the exact resolution
+    #[clap(long)]
+    pub greedy_only: bool,
+    /// Only print the lower bound given by a relaxed decision diagram compiled at the root, without any search
+    #[clap(long)]
+    pub bound_only: bool,
+    /// Check that the bound of the relaxation at the root does not exceed the proven optimum
+    #[clap(long)]
+    pub verify_bound: bool,
+    /// Solve the instance with each number of runways from 1 up to its own, and print the cost
+    /// obtained with each of them
+    #[clap(long)]
+    pub runway_count_sweep: bool,
+    /// Explain why the solution is optimal or why the search stopped
+    #[clap(long)]
+    pub explain: bool,
+    /// Improve the best solution with a local search when it is not proven optimal
+    #[clap(long)]
+    pub polish: bool,
+    /// The time limit of the local search of `--polish` in seconds
+    #[clap(long, default_value="5")]
+    pub polish_timeout: u64,
+    /// Solve the instance with and without the chain bound, within `--max-nodes` nodes (10000
+    /// when absent), and print the results side by side
+    #[clap(long)]
+    pub compare_bounds: bool,
+    /// Load and validate the instance, print the effective configuration and exit without solving
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+/// The node budget of each resolution of `--compare-bounds` when `--max-nodes` is absent
+const COMPARE_BOUNDS_NODES: usize = 10_000;
+
+/// The outcome of the solve command, which determines its exit code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolveStatus {
+    /// The solution is proven optimal, or no search was requested
+    Success,
+    /// A solution was found but it is not proven optimal
+    Unproven,
+    /// No solution was found
+    NoSolution,
+}
+
+impl SolveStatus {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            SolveStatus::Success => 0,
+            SolveStatus::Unproven => 2,
+            SolveStatus::NoSolution => 3,
+        }
+    }
+}
+
+/// The version of the structure of the `SolutionReport` and `RunRecord` json files, increased
+/// on each change which is not backward compatible
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The json schemas of the `SolutionReport` and `RunRecord` json files
+pub fn record_schemas() -> String {
+    let schemas = serde_json::json!({
+        "SolutionReport": schema_for!(SolutionReport),
+        "RunRecord": schema_for!(RunRecord),
+    });
+    serde_json::to_string_pretty(&schemas).unwrap()
+}
+
+/// A machine-readable summary of a resolution, with the costs and times in the original units
+/// of the instance
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SolutionReport {
+    /// The version of the structure of the report (0 for the reports written before it was versioned)
+    #[serde(default)]
+    pub schema_version: u32,
+    /// The total cost of the best solution found, if any
+    pub cost: Option<f64>,
+    pub is_exact: bool,
+    /// The best bound on the cost proven by the solver, if any
+    pub bound: Option<f64>,
+    /// The absolute gap between the cost and the bound
+    pub gap: Option<f64>,
+    /// The gap relative to the cost, in percents
+    pub relative_gap: Option<f64>,
+    /// The wall-clock duration of the resolution in seconds
+    pub duration: f64,
+    /// The ordered landings of each runway, if a solution was found
+    pub solution: Option<Vec<Vec<Landing>>>,
+    /// The deviations from the target times in the solution, if a solution was found
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breakdown: Option<CostBreakdown>,
+    /// The value of each objective for the solution, if a solution was found
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<ObjectiveValues>,
+    /// The time spent in each phase of the resolution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<PhaseTimings>,
+    /// Why the solution is optimal or why the search stopped, when it was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
+}
+
+/// The wall-clock time spent in each phase of a resolution, in seconds. The phases within the
+/// search itself are not distinguished, as the solver does not expose them.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PhaseTimings {
+    /// The validation of the instance, the construction of the model and the replay of the warm start
+    pub setup: f64,
+    /// The search of the solver, from the root to the last phase
+    pub search: f64,
+    /// The replay of the decisions of the best solution into a schedule
+    pub reconstruction: f64,
+}
+
+impl PhaseTimings {
+    pub fn total(&self) -> f64 {
+        self.setup + self.search + self.reconstruction
+    }
+}
+
+/// The deviations of a schedule from the target times, in the original units of the instance
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct CostBreakdown {
+    /// The sum of the times by which the aircrafts land before their target
+    pub total_earliness: f64,
+    /// The sum of the times by which the aircrafts land after their target
+    pub total_lateness: f64,
+    /// The number of aircrafts landing after their target
+    pub nb_late: usize,
+    /// The largest deviation of an aircraft from its target
+    pub max_deviation: f64,
+}
+
+impl CostBreakdown {
+    /// Compares the arrival of each aircraft of the schedule to its target time
+    pub fn of(instance: &AlpInstance, runways: &[Vec<(isize, usize)>]) -> Self {
+        let mut earliness = 0;
+        let mut lateness = 0;
+        let mut nb_late = 0;
+        let mut max_deviation = 0;
+
+        for (arrival, aircraft) in runways.iter().flatten().copied() {
+            let target = instance.target[aircraft];
+            earliness = add_cost(earliness, (target - arrival).max(0));
+            lateness = add_cost(lateness, (arrival - target).max(0));
+            if arrival > target {
+                nb_late += 1;
+            }
+            max_deviation = max_deviation.max((arrival - target).abs());
+        }
+
+        CostBreakdown {
+            total_earliness: instance.unscale(earliness),
+            total_lateness: instance.unscale(lateness),
+            nb_late,
+            max_deviation: instance.unscale(max_deviation),
+        }
+    }
+}
+
+/// The value of a schedule under each objective, whichever was optimized, in the original units
+/// of the instance
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct ObjectiveValues {
+    /// The total weighted deviation from the target times
+    pub total_deviation: f64,
+    /// The arrival time of the last aircraft, measured from time 0
+    pub makespan: f64,
+    /// The largest lateness of an aircraft
+    pub max_lateness: f64,
+}
+
+impl ObjectiveValues {
+    /// Evaluates the schedule with the given global factors of the earliness and lateness costs
+    pub fn of(instance: &AlpInstance, runways: &[Vec<(isize, usize)>], earliness_weight: usize, lateness_weight: usize) -> Self {
+        let mut total_deviation = 0;
+        let mut makespan = 0;
+        let mut max_lateness = 0;
+
+        for (arrival, aircraft) in runways.iter().flatten().copied() {
+            let target = instance.target[aircraft];
+            let earliness_cost = instance.earliness_cost.get(aircraft).copied().unwrap_or(1);
+            let lateness_cost = instance.lateness_cost.get(aircraft).copied().unwrap_or(1);
+            let deviation = (earliness_weight as isize).checked_mul(earliness_cost).and_then(|c| c.checked_mul((target - arrival).max(0)))
+                .zip((lateness_weight as isize).checked_mul(lateness_cost).and_then(|c| c.checked_mul((arrival - target).max(0))))
+                .and_then(|(e, l)| e.checked_add(l))
+                .expect("cost overflow");
+            total_deviation = add_cost(total_deviation, deviation);
+            makespan = makespan.max(arrival);
+            max_lateness = max_lateness.max(arrival - target);
+        }
+
+        ObjectiveValues {
+            total_deviation: instance.unscale(total_deviation),
+            makespan: instance.unscale(makespan),
+            max_lateness: instance.unscale(max_lateness),
+        }
+    }
+}
+
+/// The settings and results of a run of the solve command, meant for automated experiments
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RunRecord {
+    /// The version of the structure of the record
+    pub schema_version: u32,
+    pub instance: String,
+    pub objective: Objective,
+    pub earliness_weight: usize,
+    pub lateness_weight: usize,
+    pub width: usize,
+    pub width_mode: WidthMode,
+    pub relaxation: RelaxationMode,
+    pub chain_bound: bool,
+    /// The timeout in seconds
+    pub timeout: u64,
+    pub max_nodes: Option<usize>,
+    pub fringe: FringeType,
+    pub threads: usize,
+    pub sort_aircraft: AircraftOrder,
+    pub time_scale: Option<f64>,
+    pub warm_start: bool,
+    pub progress: bool,
+    pub first_solution: bool,
+    /// The total cost of the best solution found, if any
+    pub cost: Option<f64>,
+    pub is_exact: bool,
+    /// The best bound on the cost proven by the solver, if any
+    pub bound: Option<f64>,
+    pub gap: Option<f64>,
+    /// The gap relative to the cost, in percents
+    pub relative_gap: Option<f64>,
+    /// The wall-clock duration of the resolution in seconds
+    pub duration: f64,
+    /// The number of sub-problems popped from the fringe
+    pub explored: usize,
+    /// The peak size of the fringe
+    pub max_fringe_size: usize,
+    /// How the resolution ended
+    pub termination: Termination,
+}
+
+impl RunRecord {
+    /// The record of the given resolution of the given instance, named after its file
+    pub fn new(name: String, instance: &AlpInstance, opts: &SolveOptions, outcome: &SolveOutcome) -> Self {
+        let (gap, relative_gap) = outcome.gap();
+        RunRecord {
+            schema_version: SCHEMA_VERSION,
+            instance: name,
+            objective: opts.objective,
+            earliness_weight: opts.earliness_weight,
+            lateness_weight: opts.lateness_weight,
+            width: opts.width,
+            width_mode: opts.width_mode,
+            relaxation: opts.relaxation,
+            chain_bound: opts.chain_bound,
+            timeout: opts.timeout,
+            max_nodes: opts.max_nodes,
+            fringe: opts.fringe,
+            threads: opts.threads,
+            sort_aircraft: opts.sort_aircraft,
+            time_scale: instance.time_scale,
+            warm_start: opts.warm_start.is_some(),
+            progress: opts.progress,
+            first_solution: opts.first_solution,
+            cost: outcome.cost.map(|c| instance.unscale(c)),
+            is_exact: outcome.is_exact,
+            bound: outcome.bound.map(|b| instance.unscale(b)),
+            gap: gap.map(|g| instance.unscale(g)),
+            relative_gap,
+            duration: outcome.duration.as_secs_f64(),
+            explored: outcome.explored,
+            max_fringe_size: outcome.max_fringe_size,
+            termination: outcome.termination(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct Landing {
+    pub aircraft: usize,
+    pub class: usize,
+    pub arrival_time: f64,
+}
+
+/// The settings of a resolution, mirroring the options of the solve command
+#[derive(Debug, Clone)]
+pub struct SolveOptions {
+    pub objective: Objective,
+    /// The factors applied to the earliness and lateness costs of all the aircrafts
+    pub earliness_weight: usize,
+    pub lateness_weight: usize,
+    /// The max number of nodes in a layer, or the factor of the number of unassigned aircrafts
+    pub width: usize,
+    pub width_mode: WidthMode,
+    pub relaxation: RelaxationMode,
+    /// Whether the bound accounts for the earliest arrival of each remaining aircraft
+    pub chain_bound: bool,
+    /// The timeout in seconds
+    pub timeout: u64,
+    /// The max number of sub-problems explored, if any
+    pub max_nodes: Option<usize>,
+    pub fringe: FringeType,
+    /// The number of threads used by the solver
+    pub threads: usize,
+    pub sort_aircraft: AircraftOrder,
+    /// An initial solution given as the ordered landings of each runway
+    pub warm_start: Option<Vec<Vec<Landing>>>,
+    /// Print each improvement of the incumbent
+    pub progress: bool,
+    /// Stop as soon as a solution is found
+    pub first_solution: bool,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        SolveOptions {
+            objective: Objective::TotalDeviation,
+            earliness_weight: 1,
+            lateness_weight: 1,
+            width: 100,
+            width_mode: WidthMode::Fixed,
+            relaxation: RelaxationMode::Merge,
+            chain_bound: false,
+            timeout: 60,
+            max_nodes: None,
+            fringe: FringeType::NodupMaxub,
+            threads: 1,
+            sort_aircraft: AircraftOrder::None,
+            warm_start: None,
+            progress: false,
+            first_solution: false,
+        }
+    }
+}
+
+/// The result of a resolution
+#[derive(Debug, Clone)]
+pub struct SolveOutcome {
+    /// The cost of the best solution found, if any
+    pub cost: Option<isize>,
+    /// The best bound on the cost proven by the solver, if any
+    pub bound: Option<isize>,
+    pub is_exact: bool,
+    pub duration: Duration,
+    /// The `(arrival, aircraft)` landings of each runway in the best solution found, if any
+    pub schedule: Option<Vec<Vec<(isize, usize)>>>,
+    /// The cost of the warm start solution, if any
+    pub warm_start_cost: Option<isize>,
+    /// The number of sub-problems popped from the fringe
+    pub explored: usize,
+    /// The peak size of the fringe
+    pub max_fringe_size: usize,
+    /// The limit which stopped the search, if it did not complete
+    pub cutoff: Option<CutoffKind>,
+    /// The decisions of the model leading to the best solution found, if any
+    pub decisions: Option<Vec<Decision>>,
+    pub timings: PhaseTimings,
+}
+
+/// The reasons why an instance cannot be solved
+#[derive(Debug, Clone)]
+pub enum SolveError {
+    /// The instance is malformed
+    InvalidInstance(Vec<InstanceError>),
+    /// The warm start solution is rejected by the model
+    InvalidWarmStart(String),
+    /// The solution cannot be replayed through the model
+    InvalidSolution(String),
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::InvalidInstance(errors) => {
+                write!(f, "invalid instance")?;
+                for error in errors.iter() {
+                    write!(f, "\n{error}")?;
+                }
+                Ok(())
+            },
+            SolveError::InvalidWarmStart(e) => write!(f, "invalid warm start: {e}"),
+            SolveError::InvalidSolution(e) => write!(f, "invalid solution: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// Reads a solution report from the given json file
+pub fn read_report(path: &str) -> Result<SolutionReport, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    serde_json::from_reader(BufReader::new(file)).map_err(|e| e.to_string())
+}
+
+/// Solves the given instance with the given options
+pub fn solve_instance(instance: &AlpInstance, opts: &SolveOptions) -> Result<SolveOutcome, SolveError> {
+    let start = Instant::now();
+
+    let errors = instance.validate();
+    if !errors.is_empty() {
+        return Err(SolveError::InvalidInstance(errors));
+    }
+
+    if instance.nb_aircrafts == 0 || instance.nb_runways == 0 {
+        return Ok(degenerate_outcome(instance));
+    }
+
+    let (problem, order, rank) = build_problem(instance, opts)?;
+
+    let warm_start = match opts.warm_start.as_ref() {
+        Some(solution) => Some(replay_permuted(&problem, &rank, solution).map_err(SolveError::InvalidWarmStart)?),
+        None => None,
+    };
+    let warm_start_cost = warm_start.as_ref().map(|(value, _)| -value);
+    let setup = start.elapsed();
+
+    let Resolution { best_value, best_bound, is_exact, duration, best_solution, explored, max_fringe_size, cutoff } =
+        resolve(&problem, opts, warm_start);
+
+    let start = Instant::now();
+    let schedule = best_solution.as_ref().map(|decisions| reconstruct(&problem, decisions).into_iter()
+        .map(|landings| landings.into_iter().map(|(arrival, aircraft)| (arrival, order[aircraft])).collect())
+        .collect());
+    let reconstruction = start.elapsed();
+
+    Ok(SolveOutcome {
+        cost: best_value.map(|v| -v),
+        bound: if best_bound == isize::MAX { None } else { Some(-best_bound) },
+        is_exact,
+        duration,
+        schedule,
+        warm_start_cost,
+        explored,
+        max_fringe_size,
+        cutoff,
+        decisions: best_solution,
+        timings: PhaseTimings {
+            setup: setup.as_secs_f64(),
+            search: duration.as_secs_f64(),
+            reconstruction: reconstruction.as_secs_f64(),
+        },
+    })
+}
+
+/// The aircrafts of the instance in the given order
+fn aircraft_order(instance: &AlpInstance, sort: AircraftOrder) -> Vec<usize> {
+    let mut order = (0..instance.nb_aircrafts).collect::<Vec<usize>>();
+    match sort {
+        AircraftOrder::None => (),
+        AircraftOrder::ByTarget => order.sort_by_key(|a| instance.target[*a]),
+        AircraftOrder::ByLatest => order.sort_by_key(|a| instance.latest[*a]),
+    }
+    order
+}
+
+/// Whether the given order of the aircrafts changes the relative order of the aircrafts of a
+/// class, which restricts the model to other schedules than the order of the instance
+pub fn reorders_classes(instance: &AlpInstance, sort: AircraftOrder) -> bool {
+    let mut last = vec![None; instance.nb_classes];
+    aircraft_order(instance, sort).into_iter().any(|aircraft| {
+        let previous = last[instance.classes[aircraft]].replace(aircraft);
+        previous.map_or(false, |previous| previous > aircraft)
+    })
+}
+
+/// The problem solved for the given instance and options, along with the aircraft `order[i]` of
+/// the instance behind each aircraft `i` of the problem and the aircraft `rank[a]` of the problem
+/// behind each aircraft `a` of the instance
+pub(super) fn build_problem(instance: &AlpInstance, opts: &SolveOptions) -> Result<(Alp, Vec<usize>, Vec<usize>), SolveError> {
+    let order = aircraft_order(instance, opts.sort_aircraft);
+    let mut rank = vec![0; order.len()];
+    for (i, aircraft) in order.iter().copied().enumerate() {
+        rank[aircraft] = i;
+    }
+
+    let permuted = instance.permute(&order);
+    let errors = permuted.validate();
+    if !errors.is_empty() {
+        return Err(SolveError::InvalidInstance(errors));
+    }
+
+    let mut problem = Alp::new(permuted);
+    problem.objective = opts.objective;
+    problem.earliness_weight = opts.earliness_weight as isize;
+    problem.lateness_weight = opts.lateness_weight as isize;
+
+    Ok((problem, order, rank))
+}
+
+/// Replays a solution given with the aircrafts of the instance through the problem built by
+/// `build_problem`
+fn replay_permuted(problem: &Alp, rank: &[usize], solution: &[Vec<Landing>]) -> Result<(isize, Vec<Decision>), String> {
+    if let Some(l) = solution.iter().flatten().find(|l| l.aircraft >= rank.len()) {
+        return Err(format!("aircraft {} does not exist", l.aircraft));
+    }
+    let solution = solution.iter()
+        .map(|landings| landings.iter().map(|l| Landing { aircraft: rank[l.aircraft], ..*l }).collect())
+        .collect::<Vec<Vec<Landing>>>();
+    replay(problem, &solution)
+}
+
+/// The Graphviz DOT graph of a sequence of decisions leading to the given schedule in the
+/// problem solved for the given instance and options, obtained by replaying its landings by
+/// increasing arrival time
+pub fn solution_dot(instance: &AlpInstance, opts: &SolveOptions, schedule: &[Vec<(isize, usize)>]) -> Result<String, SolveError> {
+    let (problem, order, rank) = build_problem(instance, opts)?;
+    let (_, decisions) = replay_permuted(&problem, &rank, &to_landings(instance, schedule))
+        .map_err(SolveError::InvalidSolution)?;
+    Ok(decision_path_dot(&problem, &decisions, &order))
+}
+
+/// The lower bound on the cost of the given instance given by a relaxed decision diagram
+/// compiled from the root with the objective, weights and width of the given options, without
+/// any search, or `None` when the relaxation proves that the instance has no solution
+pub fn root_bound(instance: &AlpInstance, opts: &SolveOptions) -> Result<Option<isize>, SolveError> {
+    let errors = instance.validate();
+    if !errors.is_empty() {
+        return Err(SolveError::InvalidInstance(errors));
+    }
+    if instance.nb_aircrafts == 0 || instance.nb_runways == 0 {
+        return Ok(degenerate_outcome(instance).cost);
+    }
+
+    let (problem, _, _) = build_problem(instance, opts)?;
+    let mut relaxation = AlpRelax::new(problem.clone());
+    relaxation.chain_bound = opts.chain_bound;
+
+    let width = match (opts.relaxation, opts.width_mode) {
+        (RelaxationMode::None, _) => usize::MAX,
+        (RelaxationMode::Merge, WidthMode::Fixed) => opts.width,
+        (RelaxationMode::Merge, WidthMode::PerLayer) => opts.width.saturating_mul(instance.nb_aircrafts),
+    };
+
+    let root = problem.initial_state();
+    let fast_bound = -(problem.initial_value() + relaxation.fast_upper_bound(&root));
+    Ok(relaxed_value(&problem, &relaxation, width.max(1)).map(|value| (-value).max(fast_bound)))
+}
+
+/// The value of the best path of the relaxed decision diagram compiled from the root of the
+/// given problem, whose layers are cut down to the given width by merging their least promising
+/// nodes, or `None` when no path reaches the last layer
+fn relaxed_value(problem: &Alp, relaxation: &AlpRelax, width: usize) -> Option<isize> {
+    let ranking = AlpRanking;
+    let mut layer = vec![(problem.initial_state(), problem.initial_value())];
+
+    for depth in 0..problem.nb_variables() {
+        let mut next = HashMap::new();
+        for (state, value) in layer.iter() {
+            problem.for_each_in_domain(Variable(depth), state, &mut |decision: Decision| {
+                let value = add_cost(*value, problem.transition_cost(state, decision));
+                let best = next.entry(problem.transition(state, decision)).or_insert(value);
+                *best = value.max(*best);
+            });
+        }
+
+        let mut next = next.into_iter().collect::<Vec<(AlpState, isize)>>();
+        if next.len() > width {
+            next.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| ranking.compare(&b.0, &a.0)));
+            let merged = next.split_off(width - 1);
+            let state = relaxation.merge(&mut merged.iter().map(|(state, _)| state));
+            let value = merged.iter().map(|(_, value)| *value).max().unwrap();
+            match next.iter_mut().find(|(s, _)| *s == state) {
+                Some((_, best)) => *best = value.max(*best),
+                None => next.push((state, value)),
+            }
+        }
+        layer = next;
+    }
+
+    layer.into_iter().map(|(_, value)| value).max()
+}
+
+/// Without any aircraft, the empty schedule is optimal. Without any runway, no aircraft can land
+/// and the instance is proven infeasible.
+pub(super) fn degenerate_outcome(instance: &AlpInstance) -> SolveOutcome {
+    let feasible = instance.nb_aircrafts == 0;
+    SolveOutcome {
+        cost: if feasible { Some(0) } else { None },
+        bound: if feasible { Some(0) } else { None },
+        is_exact: true,
+        duration: Duration::ZERO,
+        schedule: if feasible { Some(vec![vec![]; instance.nb_runways]) } else { None },
+        warm_start_cost: None,
+        explored: 0,
+        max_fringe_size: 0,
+        cutoff: None,
+        decisions: if feasible { Some(vec![]) } else { None },
+        timings: PhaseTimings::default(),
+    }
+}
+
+/// The outcome of a call to the solver
+struct Resolution {
+    pub best_value: Option<isize>,
+    /// The best upper bound on the value proven by the solver
+    pub best_bound: isize,
+    pub is_exact: bool,
+    pub duration: Duration,
+    pub best_solution: Option<Vec<Decision>>,
+    /// The number of sub-problems popped from the fringe
+    pub explored: usize,
+    /// The peak size of the fringe
+    pub max_fringe_size: usize,
+    /// The limit which stopped the search, if it did not complete
+    pub cutoff: Option<CutoffKind>,
+}
+
+/// Solves the given problem with the max width (or width factor), timeout and number of threads
+/// of the given options, starting from the given incumbent value and solution if any.
+///
+/// The solutions reached by the decision diagrams are reported to a shared incumbent while the
+/// solver runs, so that each improvement can be printed and the search can stop at the first
+/// solution when it is requested. The sub-problems popped from the fringe then carry the best
+/// value the solver holds for them, and the decision diagrams track the best value reaching
+/// each exact state, so that the values reported are those of the solutions the solver keeps.
+fn resolve(problem: &Alp, opts: &SolveOptions, warm_start: Option<(isize, Vec<Decision>)>) -> Resolution {
+    let incumbent = Arc::new(Incumbent::new(warm_start.as_ref().map(|(value, _)| *value)));
+    let reporting = opts.progress || opts.first_solution;
+    let mut problem = problem.clone();
+    if reporting {
+        problem.incumbent = Some(incumbent.clone());
+    }
+
+    let mut relaxation = AlpRelax::new(problem.clone());
+    relaxation.chain_bound = opts.chain_bound;
+
+    let fixed_width = FixedWidth(opts.width);
+    let remaining_width = RemainingWidth::new(opts.width);
+    let unbounded_width = FixedWidth(usize::MAX);
+    let width: &(dyn WidthHeuristic<AlpState> + Send + Sync) = match (opts.relaxation, opts.width_mode) {
+        (RelaxationMode::None, _) => &unbounded_width,
+        (RelaxationMode::Merge, WidthMode::Fixed) => &fixed_width,
+        (RelaxationMode::Merge, WidthMode::PerLayer) => &remaining_width,
+    };
+    let ranking = AlpRanking;
+    let mut nodup_fringe = NoDupFringe::new(MaxUB::new(&ranking));
+    let mut simple_fringe = SimpleFringe::new(MaxUB::new(&ranking));
+    let fringe: &mut (dyn Fringe<State = AlpState> + Send + Sync) = match opts.fringe {
+        FringeType::NodupMaxub => &mut nodup_fringe,
+        FringeType::SimpleMaxub => &mut simple_fringe,
+    };
+    let explored = AtomicUsize::new(0);
+    let mut fringe = StatsFringe::new(fringe, &explored);
+    if reporting {
+        fringe = fringe.revaluing(revalue);
+    }
+
+    let trip = Trip::default();
+    let budget = Budget::new(Duration::from_secs(opts.timeout), opts.max_nodes, &explored, &trip);
+    let first_solution = FirstSolution::new(budget, &incumbent, opts.first_solution, &trip);
+    let cutoff = Progress::new(first_solution, &incumbent, opts.progress);
+
+    let start = Instant::now();
+    let (completion, best_bound, solution) = {
+        let mut solver = ParBarrierSolverFc::custom(&problem, &relaxation, &ranking, width, &cutoff, &mut fringe, opts.threads);
+        if let Some((value, solution)) = warm_start {
+            solver.set_primal(value, solution);
+        }
+        let completion = solver.maximize();
+        (completion, solver.best_upper_bound(), solver.best_solution())
+    };
+    let duration = start.elapsed();
+    cutoff.report();
+
+    let Completion { best_value, is_exact } = completion;
+    let best_solution = best_value.and(solution);
+
+    let best_bound = match best_value {
+        Some(value) if is_exact => value,
+        _ => best_bound,
+    };
+
+    // the solver only gives up on proving the optimum when a cutoff stops it
+    let cutoff = if is_exact { None } else { trip.kind().or(Some(CutoffKind::Time)) };
+
+    Resolution {
+        best_value,
+        best_bound,
+        is_exact,
+        duration,
+        best_solution,
+        explored: explored.load(Ordering::Relaxed),
+        max_fringe_size: fringe.max_size,
+        cutoff,
+    }
+}
+
+impl SolveOutcome {
+    /// How the resolution ended
+    pub fn termination(&self) -> Termination {
+        Termination::new(self.cost.is_some(), self.cutoff)
+    }
+
+    /// The absolute and relative (in percents) gaps between the cost and the bound
+    pub fn gap(&self) -> (Option<isize>, Option<f64>) {
+        match (self.cost, self.bound) {
+            (Some(cost), Some(bound)) => {
+                let gap = cost - bound;
+                let relative_gap = if cost == 0 { 0.0 } else { 100.0 * gap as f64 / cost.abs() as f64 };
+                (Some(gap), Some(relative_gap))
+            },
+            _ => (None, None),
+        }
+    }
+}
+
+/// The restriction of the schedules over which the optimum is proven when the aircrafts may land
+/// before their target time
+pub const EARLY_LANDING_RESTRICTION: &str = "each aircraft lands as soon as possible either from its earliest time or from its target time";
+
+/// A sentence explaining how the given resolution ended, with the costs in the original units of
+/// the given instance
+pub fn explain_completion(instance: &AlpInstance, outcome: &SolveOutcome) -> String {
+    let seconds = outcome.duration.as_secs_f64();
+    let bound = match outcome.bound {
+        Some(bound) => format!("best bound {}", instance.unscale(bound)),
+        None => "no bound".to_string(),
+    };
+    let progress = match (outcome.cost, outcome.gap()) {
+        (Some(cost), (_, Some(relative_gap))) => format!("with value {} and a {relative_gap:.2}% gap; {bound}", instance.unscale(cost)),
+        (Some(cost), _) => format!("with value {}; {bound}", instance.unscale(cost)),
+        (None, _) => format!("without any solution; {bound}"),
+    };
+
+    match outcome.termination() {
+        Termination::Optimal if !instance.earliest.is_empty() => format!("Proven optimal among the schedules where {EARLY_LANDING_RESTRICTION}: the search exhausted the fringe in {seconds:.1}s, the best value is {}.",
+            outcome.cost.map_or(0.0, |cost| instance.unscale(cost))),
+        Termination::Optimal => format!("Proven optimal: the search exhausted the fringe in {seconds:.1}s, the best value is {}.",
+            outcome.cost.map_or(0.0, |cost| instance.unscale(cost))),
+        Termination::Infeasible => format!("Proven infeasible: the search exhausted the fringe in {seconds:.1}s without finding any schedule."),
+        Termination::Timeout => format!("Stopped by timeout after {seconds:.1}s {progress}."),
+        Termination::MaxNodes => format!("Stopped after exploring {} nodes in {seconds:.1}s {progress}.", outcome.explored),
+        Termination::FirstSolution => format!("Stopped at the first solution after {seconds:.1}s {progress}."),
+    }
+}
+
+/// Replays the given decisions and returns the `(arrival, aircraft)` landings of each runway.
+///
+/// When the runways are symmetric, the runway of a decision refers to a position in the sorted
+/// runway states of the current state, so the physical runway behind each position is tracked
+/// along the transitions.
+pub fn reconstruct(problem: &Alp, decisions: &[Decision]) -> Vec<Vec<(isize, usize)>> {
+    let mut runways = vec![vec![]; problem.instance.nb_runways];
+    let mut slots = (0..problem.instance.nb_runways)
+        .map(|r| (RunwayState::default(), r))
+        .collect::<Vec<(RunwayState, usize)>>();
+    let mut cur = problem.initial_state();
+
+    for decision in decisions.iter().copied() {
+        if decision.value == -1 {
+            continue;
+        }
+
+        let AlpDecision { class, runway, early } = problem.from_decision(decision.value);
+        let aircraft = problem.next[class][cur.rem[class]];
+        let arrival = problem.get_arrival_time(&cur, aircraft, runway, early);
+
+        runways[slots[runway].1].push((arrival, aircraft));
+        slots[runway].0 = problem.landed(arrival, class);
+        if problem.symmetric_runways {
+            slots.sort_unstable();
+        }
+
+        cur = problem.transition(&cur, decision);
+        debug_assert!(slots.iter().zip(cur.info.iter()).all(|(slot, info)| slot.0 == *info));
+    }
+
+    runways
+}
+
+/// Replays the landings of the given solution through the model and returns the resulting value
+/// along with the decisions leading to it, or the reason why the model rejects the solution.
+///
+/// The landings are replayed by increasing arrival time, the arrivals are however recomputed by
+/// the model which lands each aircraft as early as possible on its runway, before its target time
+/// only if the solution does so.
+pub fn replay(problem: &Alp, solution: &[Vec<Landing>]) -> Result<(isize, Vec<Decision>), String> {
+    if solution.len() != problem.instance.nb_runways {
+        return Err(format!("the solution has {} runways instead of {}", solution.len(), problem.instance.nb_runways));
+    }
+
+    let mut landings = solution.iter().enumerate()
+        .flat_map(|(runway, landings)| landings.iter().map(move |l| (l.arrival_time, l.aircraft, runway)))
+        .collect::<Vec<(f64, usize, usize)>>();
+    landings.sort_unstable_by(|a, b| a.0.total_cmp(&b.0).then((a.1, a.2).cmp(&(b.1, b.2))));
+
+    if landings.len() != problem.instance.nb_aircrafts {
+        return Err(format!("the solution lands {} aircrafts instead of {}", landings.len(), problem.instance.nb_aircrafts));
+    }
+
+    let mut slots = (0..problem.instance.nb_runways)
+        .map(|r| (RunwayState::default(), r))
+        .collect::<Vec<(RunwayState, usize)>>();
+    let mut cur = problem.initial_state();
+    let mut value = problem.initial_value();
+    let mut decisions = vec![];
+
+    for (depth, (time, aircraft, runway)) in landings.into_iter().enumerate() {
+        if aircraft >= problem.instance.nb_aircrafts {
+            return Err(format!("aircraft {aircraft} does not exist"));
+        }
+
+        let class = problem.instance.classes[aircraft];
+        if cur.rem[class] == 0 || problem.next[class][cur.rem[class]] != aircraft {
+            return Err(format!("aircraft {aircraft} is not the next aircraft of class {class} to land"));
+        }
+        if !problem.instance.is_eligible(aircraft, runway) {
+            return Err(format!("aircraft {aircraft} cannot land on runway {runway}"));
+        }
+        if !problem.predecessors_scheduled(&cur, aircraft) {
+            return Err(format!("aircraft {aircraft} lands before an aircraft that must precede it"));
+        }
+
+        let slot = slots.iter().position(|(_, r)| *r == runway).unwrap();
+        // an aircraft landing before its target time is replayed in the early mode
+        let early = time < problem.instance.unscale(problem.instance.target[aircraft]);
+        let arrival = problem.get_arrival_time(&cur, aircraft, slot, early);
+        if arrival > problem.instance.latest[aircraft] {
+            return Err(format!("aircraft {aircraft} cannot land on runway {runway} before its latest time {}, the earliest possible arrival is {arrival}", problem.instance.latest[aircraft]));
+        }
+
+        let decision = Decision { variable: Variable(depth), value: problem.to_decision(&AlpDecision { class, runway: slot, early }) };
+        value = add_cost(value, problem.transition_cost(&cur, decision));
+        cur = problem.transition(&cur, decision);
+        decisions.push(decision);
+
+        slots[slot].0 = problem.landed(arrival, class);
+        if problem.symmetric_runways {
+            slots.sort_unstable();
+        }
+    }
+
+    Ok((value, decisions))
+}
+
+/// A constraint violated by a reconstructed schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// Two consecutive aircrafts on a runway land too close to each other
+    Separation { runway: usize, first: usize, second: usize, expected: isize, actual: isize },
+    /// An aircraft lands before its earliest time
+    Earliest { aircraft: usize, earliest: isize, arrival: isize },
+    /// An aircraft lands before it appears
+    Appearance { aircraft: usize, appearance: isize, arrival: isize },
+    /// An aircraft lands after its latest time
+    Latest { aircraft: usize, latest: isize, arrival: isize },
+    /// An aircraft lands on a runway it is not allowed to use
+    Eligibility { aircraft: usize, runway: usize },
+    /// An aircraft lands on a runway while it is closed
+    Closure { aircraft: usize, runway: usize, arrival: isize },
+    /// An aircraft lands before another one that must precede it
+    Precedence { before: usize, after: usize },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::Separation { runway, first, second, expected, actual } =>
+                write!(f, "runway {runway}: aircraft {second} lands {actual} after aircraft {first}, expected at least {expected}"),
+            Violation::Earliest { aircraft, earliest, arrival } =>
+                write!(f, "aircraft {aircraft} lands at {arrival} before its earliest time {earliest}"),
+            Violation::Appearance { aircraft, appearance, arrival } =>
+                write!(f, "aircraft {aircraft} lands at {arrival} before its appearance time {appearance}"),
+            Violation::Latest { aircraft, latest, arrival } =>
+                write!(f, "aircraft {aircraft} lands at {arrival} after its latest time {latest}"),
+            Violation::Eligibility { aircraft, runway } =>
+                write!(f, "aircraft {aircraft} lands on runway {runway} where it is not allowed"),
+            Violation::Closure { aircraft, runway, arrival } =>
+                write!(f, "aircraft {aircraft} lands at {arrival} on runway {runway} while it is closed"),
+            Violation::Precedence { before, after } =>
+                write!(f, "aircraft {after} lands before aircraft {before} which must precede it"),
+        }
+    }
+}
+
+/// Checks that the schedule of each runway respects the separation times between consecutive
+/// aircrafts as well as the time window of each aircraft, and returns all the violations found.
+pub fn validate_solution(instance: &AlpInstance, runways: &[Vec<(isize, usize)>]) -> Vec<Violation> {
+    let mut violations = vec![];
+
+    for (runway, landings) in runways.iter().enumerate() {
+        for (arrival, aircraft) in landings.iter().copied() {
+            if !instance.earliest.is_empty() && arrival < instance.earliest[aircraft] {
+                violations.push(Violation::Earliest { aircraft, earliest: instance.earliest[aircraft], arrival });
+            }
+            if !instance.appearance.is_empty() && arrival < instance.appearance[aircraft] {
+                violations.push(Violation::Appearance { aircraft, appearance: instance.appearance[aircraft], arrival });
+            }
+            if arrival > instance.latest[aircraft] {
+                violations.push(Violation::Latest { aircraft, latest: instance.latest[aircraft], arrival });
+            }
+            if !instance.is_eligible(aircraft, runway) {
+                violations.push(Violation::Eligibility { aircraft, runway });
+            }
+            if instance.next_opening(runway, arrival) != arrival {
+                violations.push(Violation::Closure { aircraft, runway, arrival });
+            }
+        }
+
+        for pair in landings.windows(2) {
+            let (arrival_i, i) = pair[0];
+            let (arrival_j, j) = pair[1];
+            let expected = instance.separation_at(runway, instance.classes[i], instance.classes[j], arrival_j);
+            let actual = arrival_j - arrival_i;
+            if actual < expected {
+                violations.push(Violation::Separation { runway, first: i, second: j, expected, actual });
+            }
+        }
+    }
+
+    let mut arrivals = vec![None; instance.nb_aircrafts];
+    for (arrival, aircraft) in runways.iter().flatten().copied() {
+        arrivals[aircraft] = Some(arrival);
+    }
+    for (before, after) in instance.precedence.iter().copied() {
+        if let (Some(arrival_before), Some(arrival_after)) = (arrivals[before], arrivals[after]) {
+            if arrival_after < arrival_before {
+                violations.push(Violation::Precedence { before, after });
+            }
+        }
+    }
+
+    violations
 }
 
 impl Solve {
-    pub fn solve(&self) {
-        let instance: AlpInstance = serde_json::from_reader(BufReader::new(File::open(&self.instance).unwrap())).unwrap();
-        let problem = Alp::new(instance);
-        let relaxation = AlpRelax::new(problem.clone());
+    pub fn solve(&self, verbosity: Verbosity) -> SolveStatus {
+        let mut instance = read_instance(&self.instance, self.format, self.nb_runways).unwrap_or_else(|e| {
+            eprintln!("{}: {e}", self.instance);
+            process::exit(1);
+        });
 
-        let width = FixedWidth(self.width);
-        let cutoff = TimeBudget::new(Duration::from_secs(self.timeout));
-        let ranking = AlpRanking;
-        let mut fringe = NoDupFringe::new(MaxUB::new(&ranking));
+        // the transforms below assume a well-formed instance
+        let errors = instance.validate();
+        if !errors.is_empty() {
+            eprintln!("{}: {}", self.instance, SolveError::InvalidInstance(errors));
+            process::exit(1);
+        }
 
-        let mut solver = ParBarrierSolverFc::custom(&problem, &relaxation, &ranking, &width, &cutoff, &mut fringe, 1);
+        if let Some(scale) = self.time_scale {
+            instance.scale_times(scale);
+        }
 
-        let Completion{best_value, is_exact} = solver.maximize();
+        if let Some(limit) = self.limit_aircraft {
+            if limit > instance.nb_aircrafts {
+                eprintln!("{}: cannot keep {limit} aircrafts out of {}", self.instance, instance.nb_aircrafts);
+                process::exit(1);
+            }
+            instance = instance.prefix(limit);
+        }
 
-        let best_value = best_value.map(|v| -v).unwrap_or(isize::MAX);
-        println!("is exact {is_exact}");
-        println!("best value {best_value}");
+        if self.symmetrize {
+            instance.symmetrize();
+        } else if !self.no_symmetry_warning && !instance.separation_is_symmetric() {
+            eprintln!("{}: warning: the separation matrix is not symmetric", self.instance);
+        }
+        if instance.has_negative_separation() {
+            eprintln!("{}: warning: the negative separation times are treated as zero", self.instance);
+        }
+        if self.tighten_latest {
+            let nb_tightened = instance.tighten_latest();
+            if verbosity != Verbosity::Quiet {
+                eprintln!("{}: tightened {nb_tightened} time windows", self.instance);
+            }
+        }
 
-        let mut runways = vec![(RunwayState {prev_time:-1, prev_class: -1}, vec![]); problem.instance.nb_runways];
-        let mut cur = problem.initial_state();
-        if let Some(decisions) = solver.best_solution() {
-            for decision in decisions {
-                let AlpDecision { class, runway } = problem.from_decision(decision.value);
-                let aircraft = problem.next[class][cur.rem[class]];
-                let arrival = problem.get_arrival_time(&cur.info, aircraft, runway);
-                
-                runways[runway].0.prev_time = arrival;
-                runways[runway].0.prev_class = problem.instance.classes[aircraft] as isize;
-                runways[runway].1.push((arrival, aircraft));
-                runways.sort_unstable();
+        if self.dry_run {
+            self.print_configuration(&instance);
+            return SolveStatus::Success;
+        }
 
-                cur = problem.transition(&cur, decision);
+        if let Some(path) = self.replay_trace.as_ref() {
+            let (cost, recorded) = replay_trace(&instance, path).unwrap_or_else(|e| {
+                eprintln!("{path}: {e}");
+                process::exit(1);
+            });
+            println!("replayed cost {}", instance.unscale(cost));
+            match recorded {
+                Some(recorded) if recorded == cost => println!("recorded cost {} (match)", instance.unscale(recorded)),
+                Some(recorded) => {
+                    eprintln!("{path}: the replayed cost {} differs from the recorded cost {}", instance.unscale(cost), instance.unscale(recorded));
+                    process::exit(1);
+                },
+                None => println!("recorded cost n/a"),
             }
-            
-            for runway in runways {
-                println!("{:?}", runway.1);
+            return SolveStatus::Success;
+        }
+
+        let warm_start = self.warm_start.as_ref().map(|path| {
+            let report = or_exit(path, read_report(path));
+            report.solution.unwrap_or_else(|| {
+                eprintln!("{path}: invalid warm start: it does not contain any solution");
+                process::exit(1);
+            })
+        });
+
+        let mut opts = SolveOptions {
+            objective: self.objective,
+            earliness_weight: self.earliness_weight,
+            lateness_weight: self.lateness_weight,
+            width: self.width,
+            width_mode: self.width_mode,
+            relaxation: self.relaxation,
+            chain_bound: self.chain_bound,
+            timeout: self.timeout,
+            max_nodes: self.max_nodes,
+            fringe: self.fringe,
+            threads: self.threads,
+            sort_aircraft: self.sort_aircraft,
+            warm_start,
+            progress: self.progress,
+            first_solution: self.first_solution,
+        };
+
+        if verbosity == Verbosity::Verbose {
+            eprintln!("{}: {} aircrafts, {} classes, {} runways", self.instance, instance.nb_aircrafts, instance.nb_classes, instance.nb_runways);
+        }
+        if verbosity != Verbosity::Quiet && reorders_classes(&instance, self.sort_aircraft) {
+            eprintln!("{}: warning: --sort-aircraft changes the landing order within a class, the optimum may differ from the one of the instance order", self.instance);
+        }
+
+        if self.bound_only {
+            let start = Instant::now();
+            let bound = root_bound(&instance, &opts).unwrap_or_else(|e| {
+                eprintln!("{}: {e}", self.instance);
+                process::exit(1);
+            });
+            match bound {
+                Some(bound) => println!("lower bound {}", instance.unscale(bound)),
+                None => println!("lower bound n/a (the relaxation has no solution)"),
             }
+            println!("duration {:.3}s", start.elapsed().as_secs_f64());
+            return SolveStatus::Success;
         }
+
+        if self.compare_bounds {
+            self.compare_bounds(&instance, &opts);
+            return SolveStatus::Success;
+        }
+
+        if self.runway_count_sweep {
+            self.runway_count_sweep(&instance, &opts);
+            return SolveStatus::Success;
+        }
+
+        if self.greedy || self.greedy_only {
+            let greedy = greedy_schedule(&instance, &opts).unwrap_or_else(|e| {
+                eprintln!("{}: {e}", self.instance);
+                process::exit(1);
+            });
+            match (greedy.cost, greedy.schedule.as_ref()) {
+                (Some(cost), Some(schedule)) => {
+                    println!("greedy value {}", instance.unscale(cost));
+                    Self::print_schedule(&instance, schedule);
+                    if opts.warm_start.is_none() {
+                        opts.warm_start = Some(to_landings(&instance, schedule));
+                    }
+                },
+                _ => println!("greedy value n/a"),
+            }
+
+            if self.greedy_only {
+                return match (greedy.cost, greedy.is_exact) {
+                    (None, _) => SolveStatus::NoSolution,
+                    (Some(_), true) => SolveStatus::Success,
+                    (Some(_), false) => SolveStatus::Unproven,
+                };
+            }
+        }
+
+        let mut outcome = solve_instance(&instance, &opts).unwrap_or_else(|e| {
+            eprintln!("{}: {e}", self.instance);
+            process::exit(1);
+        });
+
+        // the polished schedule is not reached through the decisions of the solver
+        let mut polished = false;
+        if let (true, false, Some(schedule)) = (self.polish, outcome.is_exact, outcome.schedule.as_ref()) {
+            if let Some((cost, schedule)) = polish(&instance, &opts, schedule, Duration::from_secs(self.polish_timeout)) {
+                if verbosity != Verbosity::Quiet {
+                    eprintln!("{}: polished value {} (from {})", self.instance, instance.unscale(cost), outcome.cost.map_or(f64::NAN, |c| instance.unscale(c)));
+                }
+                outcome.cost = Some(cost);
+                outcome.schedule = Some(schedule);
+                outcome.decisions = None;
+                polished = true;
+            }
+        }
+
+        if instance.nb_runways == 0 && instance.nb_aircrafts > 0 {
+            eprintln!("{}: infeasible: there is no runway to land the {} aircrafts", self.instance, instance.nb_aircrafts);
+        }
+
+        match (instance.expected_feasible, outcome.cost, outcome.is_exact) {
+            (Some(false), Some(_), _) => eprintln!("{}: warning: a schedule is found although the instance is expected to be infeasible", self.instance),
+            (Some(true), None, true) => eprintln!("{}: warning: the instance is proven infeasible although it is expected to be feasible", self.instance),
+            _ => (),
+        }
+
+        let quiet = verbosity == Verbosity::Quiet;
+        let (gap, relative_gap) = outcome.gap();
+        if !quiet {
+            Self::print_summary(&instance, &outcome);
+        }
+
+        let mut solution = None;
+        let mut breakdown = None;
+        let mut metrics = None;
+        let mut valid = true;
+        let empty = vec![vec![]; instance.nb_runways];
+        let runways = outcome.schedule.as_ref().unwrap_or(&empty);
+        if outcome.schedule.is_some() {
+            if !quiet {
+                Self::print_schedule(&instance, runways);
+
+                if self.gantt {
+                    print!("{}", gantt(&instance, runways));
+                }
+            }
+
+            let violations = validate_solution(&instance, runways);
+            for violation in violations.iter() {
+                eprintln!("{violation}");
+            }
+            if !quiet {
+                println!("schedule valid: {}", violations.is_empty());
+            }
+
+            let costs = CostBreakdown::of(&instance, runways);
+            if !quiet {
+                println!("total earliness {}", costs.total_earliness);
+                println!("total lateness {}", costs.total_lateness);
+                println!("aircraft late count {}", costs.nb_late);
+                println!("max single deviation {}", costs.max_deviation);
+            }
+            breakdown = Some(costs);
+
+            let values = ObjectiveValues::of(&instance, runways, self.earliness_weight, self.lateness_weight);
+            if !quiet {
+                println!("total deviation {}", values.total_deviation);
+                println!("makespan {}", values.makespan);
+                println!("max lateness {}", values.max_lateness);
+            }
+            metrics = Some(values);
+            valid = violations.is_empty();
+
+            solution = Some(to_landings(&instance, runways));
+        }
+
+        if let Some(path) = self.csv_out.as_ref() {
+            or_exit(path, fs::write(path, schedule_csv(&instance, runways)));
+        }
+
+        if let (Some(path), Some(schedule)) = (self.dot_out.as_ref(), outcome.schedule.as_ref()) {
+            match solution_dot(&instance, &opts, schedule) {
+                Ok(dot) => or_exit(path, fs::write(path, dot)),
+                Err(e) => eprintln!("{}: cannot write the decision graph: {e}", self.instance),
+            }
+        }
+
+        let explanation = self.explain.then(|| explain_completion(&instance, &outcome));
+
+        if let Some(path) = self.solution_out.as_ref() {
+            let report = SolutionReport {
+                schema_version: SCHEMA_VERSION,
+                cost: outcome.cost.map(|c| instance.unscale(c)),
+                is_exact: outcome.is_exact,
+                bound: outcome.bound.map(|b| instance.unscale(b)),
+                gap: gap.map(|g| instance.unscale(g)),
+                relative_gap,
+                duration: outcome.duration.as_secs_f64(),
+                solution,
+                breakdown,
+                metrics,
+                timings: Some(outcome.timings),
+                explanation: explanation.clone(),
+            };
+            or_exit(path, serde_json::to_writer_pretty(BufWriter::new(or_exit(path, File::create(path))), &report));
+        }
+
+        if let Some(path) = self.solver_stats_out.as_ref() {
+            let record = RunRecord::new(self.instance.clone(), &instance, &opts, &outcome);
+            or_exit(path, serde_json::to_writer_pretty(BufWriter::new(or_exit(path, File::create(path))), &record));
+        }
+
+        if let Some(path) = self.record_trace.as_ref() {
+            if polished {
+                eprintln!("{path}: the trace is not written since the polished schedule has no decisions");
+            } else {
+                or_exit(path, write_trace(path, &opts, &outcome));
+            }
+        }
+
+        if self.verify_bound {
+            valid &= self.verify_bound(&instance, &opts, &outcome);
+        }
+
+        if let Some(explanation) = explanation {
+            println!("{explanation}");
+        }
+
+        if !valid {
+            process::exit(1);
+        }
+
+        match (outcome.cost, outcome.is_exact) {
+            (None, _) => SolveStatus::NoSolution,
+            (Some(_), true) => SolveStatus::Success,
+            (Some(_), false) => SolveStatus::Unproven,
+        }
+    }
+
+    /// Cross-checks the bound of the relaxation at the root against the optimum, when it is proven
+    fn verify_bound(&self, instance: &AlpInstance, opts: &SolveOptions, outcome: &SolveOutcome) -> bool {
+        let cost = match (outcome.cost, outcome.is_exact) {
+            (Some(cost), true) => cost,
+            _ => {
+                eprintln!("{}: warning: the bound is not verified since no optimum was proven", self.instance);
+                return true;
+            },
+        };
+
+        let bound = match root_bound(instance, opts) {
+            Ok(Some(bound)) => bound,
+            Ok(None) => {
+                eprintln!("{}: the root relaxation has no solution but the optimum is {}", self.instance, instance.unscale(cost));
+                return false;
+            },
+            Err(e) => {
+                eprintln!("{}: {e}", self.instance);
+                return false;
+            },
+        };
+        if bound > cost {
+            eprintln!("{}: the root bound {} exceeds the optimum {}", self.instance, instance.unscale(bound), instance.unscale(cost));
+            false
+        } else {
+            println!("root bound {} <= optimum {}", instance.unscale(bound), instance.unscale(cost));
+            true
+        }
+    }
+
+    /// Solves the instance once for each combination of the optional bounds, within a short node
+    /// budget, and prints a row per combination
+    fn compare_bounds(&self, instance: &AlpInstance, opts: &SolveOptions) {
+        let max_nodes = opts.max_nodes.unwrap_or(COMPARE_BOUNDS_NODES);
+        println!("{:<8} {:>12} {:>9} {:>10}", "bounds", "best value", "is exact", "explored");
+
+        for (name, chain_bound) in [("none", false), ("chain", true)] {
+            let opts = SolveOptions {
+                chain_bound,
+                max_nodes: Some(max_nodes),
+                progress: false,
+                first_solution: false,
+                ..opts.clone()
+            };
+            let outcome = solve_instance(instance, &opts).unwrap_or_else(|e| {
+                eprintln!("{}: {e}", self.instance);
+                process::exit(1);
+            });
+
+            let best_value = outcome.cost.map_or("n/a".to_string(), |cost| instance.unscale(cost).to_string());
+            println!("{name:<8} {best_value:>12} {:>9} {:>10}", outcome.is_exact, outcome.explored);
+        }
+    }
+
+    /// Solves the instance with its first runways only, for each number of runways, and prints a
+    /// row per number of runways with the gain of the last added runway. The diminishing returns
+    /// start at the smallest number of runways whose cost is not improved by the next ones.
+    fn runway_count_sweep(&self, instance: &AlpInstance, opts: &SolveOptions) {
+        let opts = SolveOptions { progress: false, first_solution: false, warm_start: None, ..opts.clone() };
+        println!("{:<8} {:>12} {:>9} {:>12}", "runways", "best value", "is exact", "gain");
+
+        let mut costs = vec![];
+        for nb_runways in 1..=instance.nb_runways {
+            let reduced = instance.with_runways(nb_runways);
+            let cost = match solve_instance(&reduced, &opts) {
+                Ok(outcome) => {
+                    let best_value = outcome.cost.map_or("n/a".to_string(), |cost| instance.unscale(cost).to_string());
+                    let gain = match (costs.last().copied().flatten(), outcome.cost) {
+                        (Some(previous), Some(cost)) => instance.unscale(previous - cost).to_string(),
+                        _ => "n/a".to_string(),
+                    };
+                    println!("{nb_runways:<8} {best_value:>12} {:>9} {gain:>12}", outcome.is_exact);
+                    outcome.cost
+                },
+                Err(e) => {
+                    println!("{nb_runways:<8} {:>12} {:>9} {:>12}", "invalid", "n/a", "n/a");
+                    if let SolveError::InvalidInstance(errors) = e {
+                        for error in errors.iter() {
+                            eprintln!("{} with {nb_runways} runways: {error}", self.instance);
+                        }
+                    }
+                    None
+                },
+            };
+            costs.push(cost);
+        }
+
+        let best = costs.iter().flatten().copied().min();
+        match costs.iter().position(|cost| cost.is_some() && *cost == best) {
+            Some(position) => println!("no further gain beyond {} runways", position + 1),
+            None => println!("no solution with any number of runways"),
+        }
+    }
+
+    /// Prints the human-readable summary of the resolution
+    fn print_summary(instance: &AlpInstance, outcome: &SolveOutcome) {
+        if let Some(cost) = outcome.warm_start_cost {
+            println!("warm start value {}", instance.unscale(cost));
+        }
+
+        let (gap, relative_gap) = outcome.gap();
+        println!("is exact {}", outcome.is_exact);
+        if outcome.is_exact && !instance.earliest.is_empty() {
+            println!("exact among the schedules where {EARLY_LANDING_RESTRICTION}");
+        }
+        println!("best value {}", outcome.cost.map_or(isize::MAX.to_string(), |cost| instance.unscale(cost).to_string()));
+        match outcome.bound {
+            Some(bound) => println!("upper bound {}", instance.unscale(bound)),
+            None => println!("upper bound n/a"),
+        }
+        match (gap, relative_gap) {
+            (Some(0), _) => println!("gap: 0 (proven optimal)"),
+            (Some(gap), Some(relative_gap)) => println!("gap: {} ({relative_gap:.2}%)", instance.unscale(gap)),
+            _ => println!("gap: n/a"),
+        }
+        println!("nodes explored {}", outcome.explored);
+        // the solver does not report the number of layers it compiled
+        println!("layers n/a");
+        println!("max fringe size {}", outcome.max_fringe_size);
+        println!("termination {}", outcome.termination());
+        let timings = outcome.timings;
+        println!("timings: setup {:.3}s, search {:.3}s, reconstruction {:.3}s, total {:.3}s",
+            timings.setup, timings.search, timings.reconstruction, timings.total());
     }
-}
\ No newline at end of file
+
+    /// Prints the `(arrival, aircraft)` landings of each runway, along with the name of the class
+    /// of each aircraft when the classes are named
+    fn print_schedule(instance: &AlpInstance, runways: &[Vec<(isize, usize)>]) {
+        for landings in runways.iter() {
+            let landings = landings.iter()
+                .map(|(arrival, aircraft)| if instance.class_names.is_empty() {
+                    format!("({}, {aircraft})", instance.unscale(*arrival))
+                } else {
+                    format!("({}, {aircraft}, {})", instance.unscale(*arrival), instance.class_name(instance.classes[*aircraft]))
+                })
+                .collect::<Vec<String>>();
+            println!("[{}]", landings.join(", "));
+        }
+    }
+
+    /// Prints the resolved settings along with the dimensions of the problem, exits when the
+    /// instance is invalid
+    fn print_configuration(&self, instance: &AlpInstance) {
+        let errors = instance.validate();
+        if !errors.is_empty() {
+            eprintln!("{}: {}", self.instance, SolveError::InvalidInstance(errors));
+            process::exit(1);
+        }
+
+        let problem = Alp::new(instance.clone());
+
+        println!("instance {}", self.instance);
+        println!("format {:?}", self.format);
+        println!("objective {:?}", self.objective);
+        println!("earliness weight {}", self.earliness_weight);
+        println!("lateness weight {}", self.lateness_weight);
+        println!("width {}", self.width);
+        println!("width mode {:?}", self.width_mode);
+        println!("relaxation {:?}", self.relaxation);
+        println!("chain bound {}", self.chain_bound);
+        println!("timeout {}s", self.timeout);
+        println!("max nodes {}", self.max_nodes.map_or("none".to_string(), |n| n.to_string()));
+        println!("compare bounds {}", self.compare_bounds);
+        println!("runway count sweep {}", self.runway_count_sweep);
+        println!("explain {}", self.explain);
+        println!("polish {}", self.polish);
+        println!("fringe {:?}", self.fringe);
+        println!("threads {}", self.threads);
+        println!("sort aircraft {:?}", self.sort_aircraft);
+        println!("warm start {}", self.warm_start.as_deref().unwrap_or("none"));
+        println!("time scale {}", instance.time_scale.unwrap_or(1.0));
+        println!("limit aircraft {}", self.limit_aircraft.map_or("none".to_string(), |n| n.to_string()));
+        println!("symmetrize {}", self.symmetrize);
+        println!("tighten latest {}", self.tighten_latest);
+        println!("progress {}", self.progress);
+        println!("first solution {}", self.first_solution);
+        println!("greedy {}", self.greedy || self.greedy_only);
+        println!("aircrafts {}", instance.nb_aircrafts);
+        println!("classes {}", instance.nb_classes);
+        println!("runways {}", instance.nb_runways);
+        println!("symmetric runways {}", problem.symmetric_runways);
+        println!("variables {}", problem.nb_variables());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::generate::AlpGenerator;
+
+    use super::*;
+
+    /// Every complete sequence of decisions of the model from the given state
+    fn decision_paths(problem: &Alp, state: &AlpState, depth: usize, path: &mut Vec<Decision>, paths: &mut Vec<Vec<Decision>>) {
+        let variable = match problem.next_variable(depth, &mut std::iter::empty::<&AlpState>()) {
+            Some(variable) => variable,
+            None => {
+                paths.push(path.clone());
+                return;
+            },
+        };
+
+        let mut domain = vec![];
+        problem.for_each_in_domain(variable, state, &mut |decision: Decision| domain.push(decision));
+        for decision in domain {
+            path.push(decision);
+            decision_paths(problem, &problem.transition(state, decision), depth + 1, path, paths);
+            path.pop();
+        }
+    }
+
+    #[test]
+    fn reconstruct_lands_the_aircrafts_at_the_arrivals_of_the_transitions() {
+        let instance = AlpInstance::tiny(2, vec![0, 1, 0], vec![10, 11, 12], vec![40, 40, 40], vec![vec![3, 5], vec![4, 2]]);
+        let problem = Alp::new(instance.clone());
+
+        let mut paths = vec![];
+        decision_paths(&problem, &problem.initial_state(), 0, &mut vec![], &mut paths);
+        assert!(!paths.is_empty());
+
+        for decisions in paths {
+            let mut state = problem.initial_state();
+            let mut expected = vec![];
+            for decision in decisions.iter().copied().filter(|d| d.value != -1) {
+                let AlpDecision { class, runway, early } = problem.from_decision(decision.value);
+                let aircraft = problem.next[class][state.rem[class]];
+                expected.push((problem.get_arrival_time(&state, aircraft, runway, early), aircraft));
+                state = problem.transition(&state, decision);
+            }
+            expected.sort_unstable();
+
+            let runways = reconstruct(&problem, &decisions);
+            let mut landings = runways.iter().flatten().copied().collect::<Vec<(isize, usize)>>();
+            landings.sort_unstable();
+
+            assert_eq!(expected, landings);
+            assert!(validate_solution(&instance, &runways).is_empty());
+        }
+    }
+
+    #[test]
+    fn the_number_of_threads_does_not_change_the_result() {
+        let generator = AlpGenerator::from_args(&["-n", "12", "-r", "2", "-k", "3"]);
+        for seed in 0..3 {
+            let instance = generator.build_seeded(seed);
+            let outcomes = [1, 2, 4].map(|threads| {
+                let outcome = solve_instance(&instance, &SolveOptions { width: 5, threads, ..SolveOptions::default() }).unwrap();
+                (outcome.cost, outcome.is_exact)
+            });
+            assert!(outcomes[0].1, "seed {seed}");
+            assert!(outcomes.iter().all(|outcome| *outcome == outcomes[0]), "seed {seed}: {outcomes:?}");
+        }
+    }
+
+    #[test]
+    fn the_root_bound_never_exceeds_the_optimum() {
+        let mut instance = AlpInstance::tiny(1, vec![0, 1, 0, 1], vec![10, 10, 12, 30], vec![60; 4], vec![vec![4, 6], vec![5, 3]]);
+        instance.earliest = vec![0, 5, 8, 20];
+        instance.earliness_cost = vec![1, 2, 1, 3];
+        instance.lateness_cost = vec![3, 1, 2, 1];
+
+        for objective in [Objective::TotalDeviation, Objective::Makespan, Objective::MaxLateness] {
+            for (earliness_weight, lateness_weight) in [(1, 1), (2, 5)] {
+                let exact = SolveOptions { objective, earliness_weight, lateness_weight, relaxation: RelaxationMode::None, ..SolveOptions::default() };
+                let optimum = solve_instance(&instance, &exact).unwrap();
+                assert!(optimum.is_exact);
+                let optimum = optimum.cost.unwrap();
+                assert_eq!(root_bound(&instance, &exact).unwrap(), Some(optimum));
+
+                for width in [1, 2, 3] {
+                    for chain_bound in [false, true] {
+                        let opts = SolveOptions { width, chain_bound, relaxation: RelaxationMode::Merge, ..exact.clone() };
+                        let bound = root_bound(&instance, &opts).unwrap().unwrap();
+                        assert!(bound <= optimum, "{objective:?} width {width}: {bound} > {optimum}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sorting_the_aircrafts_is_flagged_when_it_reorders_a_class() {
+        let instance = AlpInstance::tiny(1, vec![0, 1, 0], vec![10, 5, 20], vec![30, 30, 15], vec![vec![1, 1], vec![1, 1]]);
+        assert!(!reorders_classes(&instance, AircraftOrder::None));
+        assert!(!reorders_classes(&instance, AircraftOrder::ByTarget));
+        assert!(reorders_classes(&instance, AircraftOrder::ByLatest));
+    }
+
+    #[test]
+    fn sorting_the_aircrafts_changes_the_decision_diagram_only_when_it_reorders_a_class() {
+        let instance = AlpInstance::tiny(1, vec![0, 0, 1], vec![10, 10, 0], vec![100, 12, 100], vec![vec![5, 5], vec![5, 6]]);
+        let size_and_optimum = |sort_aircraft| {
+            let (problem, _, _) = build_problem(&instance, &SolveOptions { sort_aircraft, ..SolveOptions::default() }).unwrap();
+            (exact_size(&problem), brute_force(&problem, &problem.initial_state(), 0))
+        };
+
+        assert_eq!(size_and_optimum(AircraftOrder::None), (5, None));
+        assert_eq!(size_and_optimum(AircraftOrder::ByTarget), (5, None));
+        // the aircraft 1 meets its latest time only when it lands before the aircraft 0
+        assert_eq!(size_and_optimum(AircraftOrder::ByLatest), (9, Some(-5)));
+    }
+
+    #[test]
+    fn the_schedule_of_sorted_aircrafts_is_polished_through_the_same_model() {
+        // the aircraft 1 lands before the aircraft 0 of its class, which only the sorted model allows
+        let instance = AlpInstance::tiny(1, vec![0, 0, 1], vec![10, 10, 0], vec![100, 12, 100], vec![vec![5, 5], vec![5, 6]]);
+        let opts = SolveOptions { sort_aircraft: AircraftOrder::ByLatest, ..SolveOptions::default() };
+        let schedule = vec![vec![(10, 1), (15, 0), (20, 2)]];
+        assert!(validate_solution(&instance, &schedule).is_empty());
+
+        let (cost, polished) = polish(&instance, &opts, &schedule, Duration::from_secs(10)).unwrap();
+        assert_eq!((cost, polished.clone()), (5, vec![vec![(0, 2), (10, 1), (15, 0)]]));
+        assert!(validate_solution(&instance, &polished).is_empty());
+    }
+
+    #[test]
+    fn the_greedy_schedule_is_a_valid_warm_start_of_sorted_aircrafts() {
+        let instance = AlpInstance::tiny(1, vec![0, 1, 0], vec![10, 5, 20], vec![30, 30, 25], vec![vec![1, 1], vec![1, 1]]);
+        for sort_aircraft in [AircraftOrder::None, AircraftOrder::ByTarget, AircraftOrder::ByLatest] {
+            let opts = SolveOptions { sort_aircraft, ..SolveOptions::default() };
+            let greedy = greedy_schedule(&instance, &opts).unwrap();
+            let schedule = greedy.schedule.unwrap();
+            assert!(validate_solution(&instance, &schedule).is_empty(), "{sort_aircraft:?}");
+
+            let opts = SolveOptions { warm_start: Some(to_landings(&instance, &schedule)), ..opts };
+            let outcome = solve_instance(&instance, &opts).unwrap();
+            assert_eq!(outcome.warm_start_cost, greedy.cost, "{sort_aircraft:?}");
+        }
+    }
+
+    #[test]
+    fn a_zero_separation_lets_two_aircrafts_land_at_the_same_time_on_the_same_runway() {
+        for separation in [0, -5] {
+            let instance = AlpInstance::tiny(1, vec![0, 0], vec![10, 10], vec![10, 10], vec![vec![separation]]);
+            let outcome = solve_instance(&instance, &SolveOptions::default()).unwrap();
+            assert_eq!(outcome.cost, Some(0), "separation {separation}");
+            assert_eq!(outcome.schedule, Some(vec![vec![(10, 0), (10, 1)]]), "separation {separation}");
+        }
+
+        let instance = AlpInstance::tiny(1, vec![0, 0], vec![10, 10], vec![10, 10], vec![vec![5]]);
+        assert!(!validate_solution(&instance, &[vec![(10, 0), (10, 1)]]).is_empty());
+        assert_eq!(solve_instance(&instance, &SolveOptions::default()).unwrap().cost, None);
+    }
+
+    #[test]
+    fn a_positive_separation_lets_two_aircrafts_land_at_the_same_time_on_different_runways() {
+        let instance = AlpInstance::tiny(2, vec![0, 0], vec![10, 10], vec![10, 10], vec![vec![5]]);
+        let schedule = vec![vec![(10, 0)], vec![(10, 1)]];
+        assert!(validate_solution(&instance, &schedule).is_empty());
+
+        let outcome = solve_instance(&instance, &SolveOptions::default()).unwrap();
+        assert_eq!(outcome.cost, Some(0));
+        let runways = outcome.schedule.unwrap();
+        assert!(runways.iter().all(|landings| landings.len() == 1));
+        assert!(validate_solution(&instance, &runways).is_empty());
+    }
+
+    #[test]
+    fn tightening_the_latest_times_keeps_the_optimum() {
+        let mut instances = vec![];
+        let generator = AlpGenerator::from_args(&["-n", "8", "-r", "2", "-k", "2", "--curfews", "--day-length", "400", "--curfew-duration", "100"]);
+        for seed in 0..5 {
+            let mut instance = generator.build_seeded(seed);
+            instance.horizon = instance.latest.iter().max().copied();
+            instances.push(instance);
+        }
+        let mut instance = AlpInstance::tiny(1, vec![0, 1, 0, 1], vec![10, 12, 20, 25], vec![60, 50, 40, 70], vec![vec![4, 6], vec![5, 3]]);
+        instance.precedence = vec![(3, 2), (1, 0)];
+        instance.horizon = Some(70);
+        instances.push(instance);
+
+        for instance in instances {
+            let mut tightened = instance.clone();
+            tightened.tighten_latest();
+            assert!(tightened.validate().is_empty());
+
+            let expected = solve_instance(&instance, &SolveOptions::default()).unwrap();
+            let outcome = solve_instance(&tightened, &SolveOptions::default()).unwrap();
+            assert!(expected.is_exact && outcome.is_exact);
+            assert_eq!(outcome.cost, expected.cost, "{instance:?}");
+        }
+    }
+
+    #[test]
+    fn each_landing_is_separated_according_to_the_regime_of_its_time() {
+        // the separation drops from 10 to 2 at time 50
+        let mut instance = AlpInstance::tiny(1, vec![0, 0, 0], vec![0, 45, 46], vec![100; 3], vec![vec![10]]);
+        instance.separation_schedule = vec![(50, vec![vec![2]])];
+        assert!(instance.validate().is_empty());
+        assert_eq!(instance.greedy_arrivals(), vec![0, 45, 50]);
+        assert!(!validate_solution(&instance, &[vec![(0, 0), (45, 1), (47, 2)]]).is_empty());
+
+        let outcome = solve_instance(&instance, &SolveOptions::default()).unwrap();
+        assert_eq!(outcome.cost, Some(4));
+        assert_eq!(outcome.schedule, Some(vec![vec![(0, 0), (45, 1), (50, 2)]]));
+
+        // the separation rises from 2 to 10 at time 50
+        let mut instance = AlpInstance::tiny(1, vec![0, 0, 0], vec![0, 49, 50], vec![100; 3], vec![vec![2]]);
+        instance.separation_schedule = vec![(50, vec![vec![10]])];
+        assert_eq!(instance.greedy_arrivals(), vec![0, 49, 59]);
+        assert!(!validate_solution(&instance, &[vec![(0, 0), (49, 1), (51, 2)]]).is_empty());
+
+        let outcome = solve_instance(&instance, &SolveOptions::default()).unwrap();
+        assert_eq!(outcome.cost, Some(9));
+    }
+
+    #[test]
+    fn an_aircraft_delayed_by_a_separation_lands_between_its_earliest_and_target_times() {
+        let mut instance = AlpInstance::tiny(1, vec![0, 0, 0], vec![0, 10, 12], vec![100; 3], vec![vec![5]]);
+        instance.earliest = vec![0, 2, 12];
+        instance.lateness_cost = vec![1, 1, 10];
+
+        // landing the second aircraft early makes room for the third one, the separation
+        // delays it from its earliest time to 5
+        let outcome = solve_instance(&instance, &SolveOptions::default()).unwrap();
+        assert!(outcome.is_exact);
+        assert_eq!(outcome.cost, Some(5));
+        assert_eq!(outcome.schedule, Some(vec![vec![(0, 0), (5, 1), (12, 2)]]));
+
+        // the model does not consider landing it at 7, closer to its target, which is cheaper
+        let closer = vec![vec![(0, 0), (7, 1), (12, 2)]];
+        assert!(validate_solution(&instance, &closer).is_empty());
+        assert_eq!(ObjectiveValues::of(&instance, &closer, 1, 1).total_deviation, 3);
+    }
+
+    #[test]
+    fn the_schedules_of_generated_instances_with_negative_release_times_are_valid() {
+        let generator = AlpGenerator::from_args(&["-n", "8", "-r", "2", "-k", "2", "--avg-interarrival-time", "10"]);
+        let mut nb_negative = 0;
+        for seed in 0..10 {
+            let instance = generator.build_seeded(seed);
+            nb_negative += (0..instance.nb_aircrafts).filter(|a| instance.release_time(*a) < 0).count();
+
+            let outcome = solve_instance(&instance, &SolveOptions::default()).unwrap();
+            assert!(validate_solution(&instance, &outcome.schedule.unwrap()).is_empty(), "seed {seed}");
+        }
+        assert!(nb_negative > 0);
+    }
+
+    #[test]
+    fn an_instance_without_aircrafts_is_solved_by_the_empty_schedule() {
+        let instance = AlpInstance::tiny(2, vec![], vec![], vec![], vec![vec![1]]);
+        let outcome = solve_instance(&instance, &SolveOptions::default()).unwrap();
+        assert_eq!(outcome.cost, Some(0));
+        assert!(outcome.is_exact);
+        assert_eq!(outcome.schedule, Some(vec![vec![], vec![]]));
+        assert_eq!(root_bound(&instance, &SolveOptions::default()).unwrap(), Some(0));
+    }
+
+    #[test]
+    fn an_instance_without_runways_is_infeasible() {
+        let instance = AlpInstance::tiny(0, vec![0, 0], vec![10, 20], vec![30, 40], vec![vec![1]]);
+        let outcome = solve_instance(&instance, &SolveOptions::default()).unwrap();
+        assert_eq!(outcome.cost, None);
+        assert!(outcome.is_exact);
+        assert_eq!(outcome.termination(), Termination::Infeasible);
+    }
+
+    /// The best value of a complete sequence of decisions of the model from the given state, if any
+    fn brute_force(problem: &Alp, state: &AlpState, depth: usize) -> Option<isize> {
+        let variable = match problem.next_variable(depth, &mut std::iter::empty::<&AlpState>()) {
+            Some(variable) => variable,
+            None => return Some(0),
+        };
+
+        let mut domain = vec![];
+        problem.for_each_in_domain(variable, state, &mut |decision: Decision| domain.push(decision));
+        domain.into_iter()
+            .filter_map(|decision| brute_force(problem, &problem.transition(state, decision), depth + 1)
+                .map(|value| value + problem.transition_cost(state, decision)))
+            .max()
+    }
+
+    #[test]
+    fn the_solver_matches_a_brute_force_enumeration_of_tiny_instances() {
+        let generator = AlpGenerator::from_args(&["-n", "5", "-r", "2", "-k", "2"]);
+        for seed in 0..5 {
+            let instance = generator.build_seeded(seed);
+            let problem = Alp::new(instance.clone());
+            let optimum = brute_force(&problem, &problem.initial_state(), 0).map(|value| -value);
+
+            let wide = solve_instance(&instance, &SolveOptions { width: 1000, ..SolveOptions::default() }).unwrap();
+            assert!(wide.is_exact);
+            assert_eq!(wide.cost, optimum, "seed {seed}");
+
+            let narrow = SolveOptions { width: 1, relaxation: RelaxationMode::Merge, ..SolveOptions::default() };
+            if let (Some(bound), Some(optimum)) = (root_bound(&instance, &narrow).unwrap(), optimum) {
+                assert!(bound <= optimum, "seed {seed}: {bound} > {optimum}");
+            }
+        }
+    }
+
+    /// The number of nodes of the exact decision diagram of the given problem, whose layers hold
+    /// the distinct states reached by the decisions from the previous layer
+    fn exact_size(problem: &Alp) -> usize {
+        let mut layer = HashSet::from([problem.initial_state()]);
+        let mut size = layer.len();
+        for depth in 0..problem.nb_variables() {
+            let mut next = HashSet::new();
+            for state in layer.iter() {
+                problem.for_each_in_domain(Variable(depth), state, &mut |decision: Decision| { next.insert(problem.transition(state, decision)); });
+            }
+            size += next.len();
+            layer = next;
+        }
+        size
+    }
+
+    #[test]
+    fn tightening_the_latest_times_prunes_the_landings_which_cannot_be_completed() {
+        // the aircraft 1 must land before the aircraft 0, hence by time 20, which it misses when
+        // the aircraft 2 lands first
+        let mut instance = AlpInstance::tiny(1, vec![0, 1, 2], vec![15, 10, 15], vec![20, 100, 100], vec![vec![10; 3]; 3]);
+        instance.precedence = vec![(1, 0)];
+        let mut tightened = instance.clone();
+        assert_eq!(tightened.tighten_latest(), 1);
+        assert_eq!(tightened.latest, vec![20, 20, 100]);
+
+        let problem = Alp::new(instance);
+        let tightened = Alp::new(tightened);
+        assert_eq!((exact_size(&tightened), exact_size(&problem)), (6, 7));
+        let optimum = brute_force(&problem, &problem.initial_state(), 0);
+        assert_eq!(optimum, brute_force(&tightened, &tightened.initial_state(), 0));
+        assert_eq!(optimum, Some(-20));
+    }
+
+    #[test]
+    fn interchangeable_aircrafts_of_equivalent_classes_lead_to_the_same_states() {
+        let instance = AlpInstance::tiny(1, vec![0, 1, 0, 1], vec![0, 0, 20, 20], vec![100; 4], vec![vec![3, 3], vec![3, 3]]);
+        assert_eq!(instance.duplicate_groups(), vec![vec![0, 1], vec![2, 3]]);
+
+        let collapsed = Alp::new(instance);
+        assert_eq!(collapsed.representative, vec![0, 0]);
+        let mut distinct = collapsed.clone();
+        distinct.representative = vec![0, 1];
+
+        assert!(exact_size(&collapsed) < exact_size(&distinct), "{} >= {}", exact_size(&collapsed), exact_size(&distinct));
+        let optimum = brute_force(&collapsed, &collapsed.initial_state(), 0);
+        assert_eq!(optimum, brute_force(&distinct, &distinct.initial_state(), 0));
+        assert_eq!(optimum, Some(-6));
+    }
+}
@@ -0,0 +1,116 @@
+use std::{fs::File, io::{self, Write, BufWriter}};
+
+use clap::Args;
+
+use crate::generate::AlpGenerator;
+use crate::instance::or_exit;
+use crate::resolution::solve::{solve_instance, RunRecord, SolveOptions, FringeType};
+use crate::resolution::width::WidthMode;
+use crate::verbosity::Verbosity;
+
+/// Generates `--count` instances with the same parameters and consecutive seeds starting from
+/// `--seed`, and solves each of them. The instances are not written, hence the output options
+/// of the generator are ignored.
+#[derive(Debug, Args)]
+pub struct SeedSweep {
+    #[command(flatten)]
+    pub generator: AlpGenerator,
+    /// max number of nodes in a layer
+    #[clap(short, long, default_value="100")]
+    pub width: usize,
+    /// Whether the max width is the same for all layers or proportional to the number of unassigned aircrafts
+    #[clap(long, value_enum, default_value="fixed")]
+    pub width_mode: WidthMode,
+    /// timeout for each instance
+    #[clap(short, long, default_value="60")]
+    pub timeout: u64,
+    /// The fringe used to select the next sub-problem to explore
+    #[clap(long, value_enum, default_value="nodup-maxub")]
+    pub fringe: FringeType,
+    /// The number of threads used by the solver for each instance
+    #[clap(long, default_value="1")]
+    pub threads: usize,
+    /// If present, the path where to write the csv results
+    #[clap(long)]
+    pub results: Option<String>,
+    /// If present, the path where to write a json record of each run per line, as soon as it ends
+    #[clap(long)]
+    pub jsonl: Option<String>,
+}
+
+impl SeedSweep {
+    /// Writes one csv row per seed, then prints the min, median and max of the resolution time
+    /// and of the number of explored nodes
+    pub fn sweep(&self, verbosity: Verbosity) {
+        self.generator.check_parameters();
+        let first_seed = self.generator.first_seed();
+
+        let mut out: Box<dyn Write> = match self.results.as_ref() {
+            Some(path) => Box::new(BufWriter::new(or_exit(path, File::create(path)))),
+            None => Box::new(io::stdout()),
+        };
+        writeln!(out, "seed,best_value,is_exact,termination,duration,explored").unwrap();
+        let mut records = self.jsonl.as_ref().map(|path| BufWriter::new(or_exit(path, File::create(path))));
+
+        let opts = SolveOptions {
+            width: self.width,
+            width_mode: self.width_mode,
+            timeout: self.timeout,
+            fringe: self.fringe,
+            threads: self.threads,
+            ..SolveOptions::default()
+        };
+
+        let mut durations = vec![];
+        let mut explored = vec![];
+        for i in 0..self.generator.count {
+            let seed = first_seed.wrapping_add(i as u128);
+            let instance = self.generator.build_seeded(seed);
+
+            let outcome = match solve_instance(&instance, &opts) {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    eprintln!("seed {seed}: {e}");
+                    continue;
+                },
+            };
+
+            let best_value = outcome.cost.map(|c| c.to_string()).unwrap_or_default();
+            if verbosity == Verbosity::Verbose {
+                eprintln!("seed {seed}: best value {best_value}, exact {}, {:.3}s", outcome.is_exact, outcome.duration.as_secs_f64());
+            }
+            writeln!(out, "{seed},{best_value},{},{},{:.3},{}", outcome.is_exact, outcome.termination(), outcome.duration.as_secs_f64(), outcome.explored).unwrap();
+            out.flush().unwrap();
+
+            if let Some(records) = records.as_mut() {
+                let record = RunRecord::new(format!("seed {seed}"), &instance, &opts, &outcome);
+                writeln!(records, "{}", serde_json::to_string(&record).unwrap()).unwrap();
+                records.flush().unwrap();
+            }
+
+            durations.push(outcome.duration.as_secs_f64());
+            explored.push(outcome.explored as f64);
+        }
+
+        if verbosity != Verbosity::Quiet {
+            match (min_median_max(&mut durations), min_median_max(&mut explored)) {
+                (Some(duration), Some(explored)) => {
+                    eprintln!("duration min {:.3}s median {:.3}s max {:.3}s", duration.0, duration.1, duration.2);
+                    eprintln!("explored min {} median {} max {}", explored.0, explored.1, explored.2);
+                },
+                _ => eprintln!("no instance was solved"),
+            }
+        }
+    }
+}
+
+/// The smallest, median and largest of the given values, if any
+fn min_median_max(values: &mut [f64]) -> Option<(f64, f64, f64)> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable_by(|a, b| a.total_cmp(b));
+    let n = values.len();
+    let median = if n % 2 == 1 { values[n / 2] } else { (values[n / 2 - 1] + values[n / 2]) / 2.0 };
+    Some((values[0], median, values[n - 1]))
+}
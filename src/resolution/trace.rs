@@ -0,0 +1,98 @@
+//! This module records the decisions of the best solution found by the solver and replays them
+//! through the model, independently of the solver, to check the cost it reports.
+//!
+//! A trace is a json lines file: its first line holds the options which shape the problem along
+//! with the reported cost, and each following line holds one decision.
+
+use std::{fs::File, io::{BufRead, BufReader, BufWriter, Write}};
+
+use ddo::{Decision, Problem, Variable};
+use serde::{Serialize, Deserialize};
+
+use crate::instance::AlpInstance;
+use crate::resolution::model::{add_cost, Objective};
+use crate::resolution::solve::{build_problem, AircraftOrder, SolveOptions, SolveOutcome};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum TraceLine {
+    Header {
+        objective: Objective,
+        earliness_weight: usize,
+        lateness_weight: usize,
+        sort_aircraft: AircraftOrder,
+        /// The cost reported by the solver, if it found a solution
+        cost: Option<isize>,
+    },
+    Decision {
+        variable: usize,
+        value: isize,
+    },
+}
+
+/// Writes the trace of the best solution of the given outcome
+pub fn write_trace(path: &str, opts: &SolveOptions, outcome: &SolveOutcome) -> std::io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+
+    let header = TraceLine::Header {
+        objective: opts.objective,
+        earliness_weight: opts.earliness_weight,
+        lateness_weight: opts.lateness_weight,
+        sort_aircraft: opts.sort_aircraft,
+        cost: outcome.cost,
+    };
+    writeln!(out, "{}", serde_json::to_string(&header).unwrap())?;
+
+    for decision in outcome.decisions.iter().flatten() {
+        let line = TraceLine::Decision { variable: decision.variable.id(), value: decision.value };
+        writeln!(out, "{}", serde_json::to_string(&line).unwrap())?;
+    }
+
+    out.flush()
+}
+
+/// Replays the decisions of the given trace through the model of the given instance, and
+/// returns the recomputed cost along with the cost reported in the trace
+pub fn replay_trace(instance: &AlpInstance, path: &str) -> Result<(isize, Option<isize>), String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut lines = BufReader::new(file).lines().enumerate();
+
+    let parse = |(i, line): (usize, std::io::Result<String>)| -> Result<TraceLine, String> {
+        let line = line.map_err(|e| e.to_string())?;
+        serde_json::from_str(&line).map_err(|e| format!("line {}: {e}", i + 1))
+    };
+
+    let (opts, cost) = match lines.next().map(parse).transpose()? {
+        Some(TraceLine::Header { objective, earliness_weight, lateness_weight, sort_aircraft, cost }) => {
+            let opts = SolveOptions { objective, earliness_weight, lateness_weight, sort_aircraft, ..SolveOptions::default() };
+            (opts, cost)
+        },
+        _ => return Err("the trace does not start with a header".to_string()),
+    };
+
+    let (problem, _, _) = build_problem(instance, &opts).map_err(|e| e.to_string())?;
+
+    let mut state = problem.initial_state();
+    let mut value = problem.initial_value();
+    for line in lines {
+        let decision = match parse(line)? {
+            TraceLine::Decision { variable, value } => Decision { variable: Variable(variable), value },
+            TraceLine::Header { .. } => return Err("the trace has more than one header".to_string()),
+        };
+
+        let mut allowed = false;
+        problem.for_each_in_domain(decision.variable, &state, &mut |d: Decision| allowed |= d.value == decision.value);
+        if !allowed {
+            return Err(format!("the decision {} of variable {} is not allowed by the model", decision.value, decision.variable.id()));
+        }
+
+        value = add_cost(value, problem.transition_cost(&state, decision));
+        state = problem.transition(&state, decision);
+    }
+
+    if state.rem.iter().any(|rem| *rem > 0) {
+        return Err("the decisions of the trace do not land all the aircrafts".to_string());
+    }
+
+    Ok((-value, cost))
+}
@@ -0,0 +1,48 @@
+//! This module defines the width heuristics available to compile the decision diagrams.
+
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::Serialize;
+use ddo::{WidthHeuristic, SubProblem};
+
+use crate::resolution::model::AlpState;
+
+/// The way the maximum width of the layers is computed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum WidthMode {
+    /// The same maximum width for all the layers
+    Fixed,
+    /// The maximum width multiplied by the number of unassigned aircrafts
+    PerLayer,
+}
+
+/// Whether the layers exceeding the maximum width are merged by the relaxation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum RelaxationMode {
+    /// The layers are restricted and relaxed to the maximum width
+    Merge,
+    /// The width is unbounded, so that the states are never merged and the first compilation is
+    /// exact. This is only practical for tiny instances.
+    None,
+}
+
+/// A width heuristic proportional to the number of aircrafts remaining to schedule in the
+/// sub-problem being compiled
+pub struct RemainingWidth {
+    factor: usize,
+}
+
+impl RemainingWidth {
+    pub fn new(factor: usize) -> Self {
+        RemainingWidth { factor }
+    }
+}
+
+impl WidthHeuristic<AlpState> for RemainingWidth {
+    fn max_width(&self, sub: &SubProblem<AlpState>) -> usize {
+        let remaining = sub.state.rem.iter().sum::<usize>();
+        self.factor * remaining.max(1)
+    }
+}
@@ -0,0 +1,25 @@
+//! This module defines how much the commands print. The results stay on the standard output
+//! while the diagnostics go to the standard error, so that the results can be piped.
+
+/// The amount of output of the commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only the errors and the files requested are written
+    Quiet,
+    /// The human-readable results are printed
+    Normal,
+    /// The progress of the solver and other diagnostics are printed as well
+    Verbose,
+}
+
+impl Verbosity {
+    pub fn from_flags(quiet: bool, verbose: bool) -> Self {
+        if quiet {
+            Verbosity::Quiet
+        } else if verbose {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        }
+    }
+}